@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE order_status_history (
+    id SERIAL PRIMARY KEY,
+    from_status SMALLINT,
+    to_status SMALLINT NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    order_id INTEGER NOT NULL,
+    changed_by_id INTEGER NOT NULL
+);
+
+CREATE INDEX order_status_history_order_id ON order_status_history (order_id);
+
+ALTER TABLE order_status_history ADD CONSTRAINT order_status_history_order_id_fkey FOREIGN KEY (order_id) REFERENCES "order" (id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE order_status_history ADD CONSTRAINT order_status_history_changed_by_id_fkey FOREIGN KEY (changed_by_id) REFERENCES "user" (id) ON DELETE RESTRICT ON UPDATE CASCADE;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+CREATE TABLE stock_reservation (
+    id SERIAL PRIMARY KEY,
+    quantity INTEGER NOT NULL,
+    expires_at TIMESTAMP(3) NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    product_item_id INTEGER NOT NULL,
+    order_item_id INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX stock_reservation_order_item_id_key ON stock_reservation (order_item_id);
+
+CREATE INDEX stock_reservation_product_item_id_expires_at ON stock_reservation (product_item_id, expires_at);
+
+ALTER TABLE stock_reservation ADD CONSTRAINT stock_reservation_product_item_id_fkey FOREIGN KEY (product_item_id) REFERENCES product_item (id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE stock_reservation ADD CONSTRAINT stock_reservation_order_item_id_fkey FOREIGN KEY (order_item_id) REFERENCES order_item (id) ON DELETE CASCADE ON UPDATE CASCADE;
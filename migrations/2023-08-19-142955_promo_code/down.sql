@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE shopping_cart DROP COLUMN IF EXISTS promo_code_id;
+
+DROP TABLE IF EXISTS promo_code_redemption;
+
+DROP TABLE IF EXISTS promo_code;
@@ -0,0 +1,44 @@
+-- Your SQL goes here
+CREATE TABLE promo_code (
+    id SERIAL PRIMARY KEY,
+    code TEXT NOT NULL,
+    discount_type SMALLINT NOT NULL,
+    amount DECIMAL(15,2) NOT NULL,
+    min_cart_total DECIMAL(15,2),
+    max_uses INTEGER,
+    max_uses_per_customer INTEGER,
+    category_ids INTEGER[] NOT NULL DEFAULT '{}',
+    product_ids INTEGER[] NOT NULL DEFAULT '{}',
+    starts_at TIMESTAMP(3),
+    expires_at TIMESTAMP(3),
+    inactive BOOLEAN NOT NULL DEFAULT false,
+    deleted BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    store_id INTEGER NOT NULL
+);
+
+CREATE TABLE promo_code_redemption (
+    id SERIAL PRIMARY KEY,
+    discount DECIMAL(15,2) NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    promo_code_id INTEGER NOT NULL,
+    customer_id INTEGER NOT NULL,
+    order_id INTEGER NOT NULL
+);
+
+ALTER TABLE shopping_cart ADD COLUMN promo_code_id INTEGER;
+
+CREATE UNIQUE INDEX promo_code_store_id_code_key ON promo_code (store_id, code);
+
+CREATE INDEX promo_code_redemption_promo_code_id_customer_id ON promo_code_redemption (promo_code_id, customer_id);
+
+ALTER TABLE promo_code ADD CONSTRAINT promo_code_store_id_fkey FOREIGN KEY (store_id) REFERENCES store (id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+ALTER TABLE promo_code_redemption ADD CONSTRAINT promo_code_redemption_promo_code_id_fkey FOREIGN KEY (promo_code_id) REFERENCES promo_code (id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+ALTER TABLE promo_code_redemption ADD CONSTRAINT promo_code_redemption_customer_id_fkey FOREIGN KEY (customer_id) REFERENCES "user" (id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+ALTER TABLE promo_code_redemption ADD CONSTRAINT promo_code_redemption_order_id_fkey FOREIGN KEY (order_id) REFERENCES "order" (id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE shopping_cart ADD CONSTRAINT shopping_cart_promo_code_id_fkey FOREIGN KEY (promo_code_id) REFERENCES promo_code (id) ON DELETE SET NULL ON UPDATE CASCADE;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS product_item_description_search_idx;
+
+DROP INDEX IF EXISTS product_name_search_idx;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+CREATE INDEX product_name_search_idx ON product USING GIN (to_tsvector('simple', name));
+
+CREATE INDEX product_item_description_search_idx ON product_item USING GIN (to_tsvector('simple', coalesce(description, '')));
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+CREATE TABLE user_session (
+    id SERIAL PRIMARY KEY,
+    refresh_token_hash TEXT NOT NULL,
+    previous_token_hash TEXT,
+    expires_at TIMESTAMP(3) NOT NULL,
+    revoked_at TIMESTAMP(3),
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX user_session_refresh_token_hash_key ON user_session (refresh_token_hash);
+
+CREATE INDEX user_session_previous_token_hash ON user_session (previous_token_hash);
+
+ALTER TABLE user_session ADD CONSTRAINT user_session_user_id_fkey FOREIGN KEY (user_id) REFERENCES "user" (id) ON DELETE CASCADE ON UPDATE CASCADE;
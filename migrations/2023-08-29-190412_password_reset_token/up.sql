@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE password_reset_token (
+    id SERIAL PRIMARY KEY,
+    token_hash TEXT NOT NULL,
+    expires_at TIMESTAMP(3) NOT NULL,
+    used_at TIMESTAMP(3),
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX password_reset_token_token_hash_key ON password_reset_token (token_hash);
+
+ALTER TABLE password_reset_token ADD CONSTRAINT password_reset_token_user_id_fkey FOREIGN KEY (user_id) REFERENCES "user" (id) ON DELETE CASCADE ON UPDATE CASCADE;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS email_verification_token;
+
+ALTER TABLE "user" DROP COLUMN IF EXISTS email_verified_at;
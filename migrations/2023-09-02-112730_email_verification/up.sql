@@ -0,0 +1,18 @@
+-- Your SQL goes here
+ALTER TABLE "user" ADD COLUMN email_verified_at TIMESTAMP(3);
+
+-- accounts created before verification existed are trusted as they are
+UPDATE "user" SET email_verified_at = created_at;
+
+CREATE TABLE email_verification_token (
+    id SERIAL PRIMARY KEY,
+    token_hash TEXT NOT NULL,
+    expires_at TIMESTAMP(3) NOT NULL,
+    used_at TIMESTAMP(3),
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX email_verification_token_token_hash_key ON email_verification_token (token_hash);
+
+ALTER TABLE email_verification_token ADD CONSTRAINT email_verification_token_user_id_fkey FOREIGN KEY (user_id) REFERENCES "user" (id) ON DELETE CASCADE ON UPDATE CASCADE;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE store_invite DROP COLUMN IF EXISTS role;
+
+ALTER TABLE "user" DROP COLUMN IF EXISTS store_role;
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+ALTER TABLE "user" ADD COLUMN store_role SMALLINT;
+
+-- the first admin of every store is the one who created it
+UPDATE "user" u SET store_role = CASE
+    WHEN u.id = (SELECT min(o.id) FROM "user" o WHERE o.managed_store_id = u.managed_store_id) THEN 0
+    ELSE 1
+END
+WHERE u.managed_store_id IS NOT NULL;
+
+ALTER TABLE store_invite ADD COLUMN role SMALLINT NOT NULL DEFAULT 1;
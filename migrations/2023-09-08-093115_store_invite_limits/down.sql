@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE store_invite
+    DROP CONSTRAINT IF EXISTS store_invite_uses_check,
+    DROP COLUMN IF EXISTS uses,
+    DROP COLUMN IF EXISTS max_uses,
+    DROP COLUMN IF EXISTS email,
+    DROP COLUMN IF EXISTS expires_at;
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+ALTER TABLE store_invite
+    ADD COLUMN expires_at TIMESTAMP(3),
+    ADD COLUMN email TEXT,
+    ADD COLUMN max_uses INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN uses INTEGER NOT NULL DEFAULT 0;
+
+UPDATE store_invite SET expires_at = created_at + INTERVAL '3 days';
+
+ALTER TABLE store_invite ALTER COLUMN expires_at SET NOT NULL;
+
+ALTER TABLE store_invite ADD CONSTRAINT store_invite_uses_check CHECK (uses <= max_uses);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS store_slug_key;
+
+ALTER TABLE store
+    DROP COLUMN IF EXISTS description,
+    DROP COLUMN IF EXISTS contact_email,
+    DROP COLUMN IF EXISTS currency,
+    DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+ALTER TABLE store
+    ADD COLUMN slug TEXT,
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD',
+    ADD COLUMN contact_email TEXT,
+    ADD COLUMN description TEXT;
+
+UPDATE store SET slug = trim(both '-' from lower(regexp_replace(name, '[^a-zA-Z0-9]+', '-', 'g')));
+
+-- stores whose name slugifies to nothing or to an already taken slug get their id appended
+UPDATE store s SET slug = CASE WHEN s.slug = '' THEN 'store' ELSE s.slug END || '-' || s.id
+WHERE s.slug = '' OR EXISTS (SELECT 1 FROM store o WHERE o.slug = s.slug AND o.id < s.id);
+
+ALTER TABLE store ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX store_slug_key ON store (slug);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS shipping_rate;
+
+ALTER TABLE product_item DROP COLUMN IF EXISTS weight_grams;
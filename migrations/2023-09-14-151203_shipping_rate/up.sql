@@ -0,0 +1,19 @@
+-- Your SQL goes here
+ALTER TABLE product_item ADD COLUMN weight_grams INTEGER CHECK (weight_grams >= 0);
+
+CREATE TABLE shipping_rate (
+    id SERIAL PRIMARY KEY,
+    rate_type SMALLINT NOT NULL,
+    -- NULL means the rate applies to every destination
+    country TEXT,
+    min_value NUMERIC(12, 2),
+    max_value NUMERIC(12, 2),
+    price NUMERIC(10, 2) NOT NULL CHECK (price >= 0),
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    shipping_method_id INTEGER NOT NULL
+);
+
+CREATE INDEX shipping_rate_shipping_method_id_idx ON shipping_rate (shipping_method_id);
+
+ALTER TABLE shipping_rate ADD CONSTRAINT shipping_rate_shipping_method_id_fkey FOREIGN KEY (shipping_method_id) REFERENCES shipping_method (id) ON DELETE CASCADE ON UPDATE CASCADE;
@@ -0,0 +1,26 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE product_item ADD COLUMN variant_id INTEGER;
+
+-- items with several option values keep only one of them
+UPDATE product_item i SET variant_id = (
+    SELECT MIN(iv.variant_id) FROM product_item_variant iv WHERE iv.product_item_id = i.id
+);
+
+ALTER TABLE product_item ADD CONSTRAINT product_item_variant_id_fk FOREIGN KEY (variant_id) REFERENCES product_variant (id) ON DELETE SET NULL ON UPDATE CASCADE;
+
+DROP TABLE IF EXISTS product_item_variant;
+
+DROP INDEX IF EXISTS product_variant_id_option_key;
+DROP INDEX IF EXISTS product_variant_option_value_key;
+
+ALTER TABLE product_variant ADD COLUMN name TEXT;
+
+UPDATE product_variant v SET name = o.name FROM product_option o WHERE o.id = v.option_id;
+
+ALTER TABLE product_variant
+    ALTER COLUMN name SET NOT NULL,
+    DROP COLUMN option_id;
+
+CREATE UNIQUE INDEX product_variant_name_value_store_key ON product_variant (name, value, store_id);
+
+DROP TABLE IF EXISTS product_option;
@@ -0,0 +1,57 @@
+-- Your SQL goes here
+CREATE TABLE product_option (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    store_id INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX product_option_name_store_key ON product_option (name, store_id);
+
+ALTER TABLE product_option ADD CONSTRAINT product_option_store_id_fkey FOREIGN KEY (store_id) REFERENCES store (id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- every existing variant name becomes an option type, its rows become the option values
+INSERT INTO product_option (name, store_id)
+SELECT DISTINCT name, store_id FROM product_variant;
+
+ALTER TABLE product_variant ADD COLUMN option_id INTEGER;
+
+UPDATE product_variant v SET option_id = o.id
+FROM product_option o
+WHERE o.name = v.name AND o.store_id = v.store_id;
+
+ALTER TABLE product_variant
+    ALTER COLUMN option_id SET NOT NULL,
+    ADD CONSTRAINT product_variant_option_id_fkey FOREIGN KEY (option_id) REFERENCES product_option (id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+DROP INDEX IF EXISTS product_variant_name_value_store_key;
+
+ALTER TABLE product_variant DROP COLUMN name;
+
+CREATE UNIQUE INDEX product_variant_option_value_key ON product_variant (option_id, value);
+
+-- lets product_item_variant check that a value belongs to the option it is stored under
+CREATE UNIQUE INDEX product_variant_id_option_key ON product_variant (id, option_id);
+
+CREATE TABLE product_item_variant (
+    product_item_id INTEGER NOT NULL,
+    variant_id INTEGER NOT NULL,
+    option_id INTEGER NOT NULL,
+    PRIMARY KEY (product_item_id, variant_id)
+);
+
+ALTER TABLE product_item_variant ADD CONSTRAINT product_item_variant_product_item_id_fkey FOREIGN KEY (product_item_id) REFERENCES product_item (id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE product_item_variant ADD CONSTRAINT product_item_variant_variant_id_option_id_fkey FOREIGN KEY (variant_id, option_id) REFERENCES product_variant (id, option_id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- an item holds at most one value per option type
+CREATE UNIQUE INDEX product_item_variant_item_option_key ON product_item_variant (product_item_id, option_id);
+
+CREATE INDEX product_item_variant_variant_id_idx ON product_item_variant (variant_id);
+
+INSERT INTO product_item_variant (product_item_id, variant_id, option_id)
+SELECT i.id, v.id, v.option_id
+FROM product_item i
+INNER JOIN product_variant v ON v.id = i.variant_id;
+
+ALTER TABLE product_item DROP COLUMN variant_id;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS product_category_parent_id_idx;
+DROP INDEX IF EXISTS product_category_store_slug_key;
+
+ALTER TABLE product_category
+    DROP CONSTRAINT IF EXISTS product_category_parent_not_self,
+    DROP COLUMN IF EXISTS parent_id,
+    DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+ALTER TABLE product_category
+    ADD COLUMN slug TEXT,
+    ADD COLUMN parent_id INTEGER REFERENCES product_category (id) ON DELETE RESTRICT ON UPDATE CASCADE,
+    ADD CONSTRAINT product_category_parent_not_self CHECK (parent_id <> id);
+
+UPDATE product_category SET slug = trim(both '-' from lower(regexp_replace(name, '[^a-zA-Z0-9]+', '-', 'g')));
+
+-- categories whose name slugifies to nothing or to a slug already taken in the store get their id appended
+UPDATE product_category c SET slug = CASE WHEN c.slug = '' THEN 'category' ELSE c.slug END || '-' || c.id
+WHERE c.slug = ''
+OR EXISTS (SELECT 1 FROM product_category o WHERE o.store_id = c.store_id AND o.slug = c.slug AND o.id < c.id);
+
+ALTER TABLE product_category ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX product_category_store_slug_key ON product_category (store_id, slug);
+
+CREATE INDEX product_category_parent_id_idx ON product_category (parent_id);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE store DROP COLUMN IF EXISTS logo_image_id;
+
+DROP TABLE IF EXISTS product_item_image;
+DROP TABLE IF EXISTS image;
@@ -0,0 +1,29 @@
+-- Your SQL goes here
+CREATE TABLE image (
+    id SERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    thumbnail_url TEXT NOT NULL,
+    storage_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK (size_bytes > 0),
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    store_id INTEGER NOT NULL REFERENCES store (id) ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+CREATE INDEX image_store_id_idx ON image (store_id);
+
+-- positions are rewritten together when images get reordered, so uniqueness is checked at commit
+CREATE TABLE product_item_image (
+    product_item_id INTEGER NOT NULL REFERENCES product_item (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    image_id INTEGER NOT NULL REFERENCES image (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    position INTEGER NOT NULL CHECK (position >= 0),
+    PRIMARY KEY (product_item_id, image_id),
+    CONSTRAINT product_item_image_position_key UNIQUE (product_item_id, position) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX product_item_image_image_id_idx ON product_item_image (image_id);
+
+ALTER TABLE store ADD COLUMN logo_image_id INTEGER REFERENCES image (id) ON DELETE SET NULL ON UPDATE CASCADE;
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE shipping_information
+    DROP COLUMN IF EXISTS phone_number,
+    DROP COLUMN IF EXISTS phone_country_code,
+    DROP COLUMN IF EXISTS postal_code,
+    DROP COLUMN IF EXISTS country,
+    DROP COLUMN IF EXISTS city,
+    DROP COLUMN IF EXISTS number,
+    DROP COLUMN IF EXISTS address_line2,
+    DROP COLUMN IF EXISTS address_line1;
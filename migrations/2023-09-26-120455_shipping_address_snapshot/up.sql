@@ -0,0 +1,30 @@
+-- Your SQL goes here
+ALTER TABLE shipping_information
+    ADD COLUMN address_line1 TEXT,
+    ADD COLUMN address_line2 TEXT,
+    ADD COLUMN number TEXT,
+    ADD COLUMN city TEXT,
+    ADD COLUMN country TEXT,
+    ADD COLUMN postal_code TEXT,
+    ADD COLUMN phone_country_code TEXT,
+    ADD COLUMN phone_number TEXT;
+
+-- existing orders keep whatever their address reads right now, the best snapshot still available
+UPDATE shipping_information s SET
+    address_line1 = a.address_line1,
+    address_line2 = a.address_line2,
+    number = a.number,
+    city = a.city,
+    country = a.country,
+    postal_code = a.postal_code,
+    phone_country_code = a.phone_country_code,
+    phone_number = a.phone_number
+FROM user_address a
+WHERE a.id = s.address_id;
+
+ALTER TABLE shipping_information
+    ALTER COLUMN address_line1 SET NOT NULL,
+    ALTER COLUMN number SET NOT NULL,
+    ALTER COLUMN city SET NOT NULL,
+    ALTER COLUMN country SET NOT NULL,
+    ALTER COLUMN postal_code SET NOT NULL;
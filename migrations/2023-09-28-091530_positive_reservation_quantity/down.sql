@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE stock_reservation DROP CONSTRAINT IF EXISTS stock_reservation_quantity_check;
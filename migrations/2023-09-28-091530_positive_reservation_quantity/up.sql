@@ -0,0 +1,4 @@
+-- Your SQL goes here
+DELETE FROM stock_reservation WHERE quantity <= 0;
+
+ALTER TABLE stock_reservation ADD CONSTRAINT stock_reservation_quantity_check CHECK (quantity > 0);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS promo_code_store_id_code_key;
+
+CREATE UNIQUE INDEX promo_code_store_id_code_key ON promo_code (store_id, code);
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+DROP INDEX IF EXISTS promo_code_store_id_code_key;
+
+-- deleted codes keep their rows for past redemptions, but their code can be taken again
+CREATE UNIQUE INDEX promo_code_store_id_code_key ON promo_code (store_id, code) WHERE deleted = false;
@@ -1,7 +1,7 @@
 use crate::{
     db::{ Pool, address::AddressService },
     error::ServiceError,
-    middleware::auth::AuthMiddleware,
+    middleware::auth::UserAuth,
     models::{ response::ResponseBody, address::UserAddress },
 };
 use actix_web::{ delete, get, post, put, web, HttpResponse };
@@ -62,11 +62,11 @@ impl FindAddressResponse {
 
 #[get("/{id}")]
 async fn find_address(
-    auth: AuthMiddleware,
+    auth: UserAuth,
     path: web::Path<i32>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let user_id = auth.user.sub.parse().unwrap();
+    let user_id = auth.user_id;
     let address_id = path.into_inner();
 
     let find_result = AddressService::find(&address_id, &user_id, false, &mut pool.get().unwrap());
@@ -80,11 +80,10 @@ async fn find_address(
 
 #[get("")]
 async fn list_addresses(
-    auth: AuthMiddleware,
+    auth: UserAuth,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let user = auth.user;
-    match AddressService::list(&user.sub.parse().unwrap(), &mut pool.get().unwrap()) {
+    match AddressService::list(&auth.user_id, &mut pool.get().unwrap()) {
         Ok(addresses) =>
             Ok(HttpResponse::Ok().json(ResponseBody::new(FindAddressResponse::new_vec(addresses)))),
         Err(e) => Err(ServiceError::NotFound { error_message: e.to_string() }),
@@ -113,14 +112,13 @@ pub struct CreateAddressBody {
 #[post("")]
 async fn create_address(
     body: web::Json<CreateAddressBody>,
-    auth: AuthMiddleware,
+    auth: UserAuth,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let user = auth.user;
     match
         AddressService::create(
             body.into_inner(),
-            &user.sub.parse().unwrap(),
+            &auth.user_id,
             &mut pool.get().unwrap()
         )
     {
@@ -133,14 +131,13 @@ async fn create_address(
 async fn edit_address(
     path: web::Path<i32>,
     body: web::Json<CreateAddressBody>,
-    auth: AuthMiddleware,
+    auth: UserAuth,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let user = auth.user;
     match
         AddressService::edit(
             &path.into_inner(),
-            &user.sub.parse().unwrap(),
+            &auth.user_id,
             body.into_inner(),
             &mut pool.get().unwrap()
         )
@@ -153,14 +150,13 @@ async fn edit_address(
 #[delete("/{id}")]
 async fn delete_address(
     path: web::Path<i32>,
-    auth: AuthMiddleware,
+    auth: UserAuth,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let user = auth.user;
     match
         AddressService::delete(
             path.into_inner(),
-            user.sub.parse().unwrap(),
+            auth.user_id,
             &mut pool.get().unwrap()
         )
     {
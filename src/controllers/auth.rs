@@ -1,9 +1,16 @@
 use crate::{
-    db::{ Pool, user::UserService },
+    db::{
+        Pool,
+        user::UserService,
+        password_reset::PasswordResetService,
+        email_verification::EmailVerificationService,
+    },
     error::ServiceError,
+    mail::MailTransport,
+    middleware::auth::UserAuth,
     models::{ response::ResponseBody, user::UserType },
 };
-use actix_web::{ post, web, HttpResponse, Result };
+use actix_web::{ get, post, web, HttpResponse, Result };
 use serde::Deserialize;
 use validator::Validate;
 
@@ -24,9 +31,10 @@ pub struct UserSignupPayload {
 #[post("/signup")]
 async fn signup(
     body: web::Json<UserSignupPayload>,
+    mailer: web::Data<dyn MailTransport>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    match UserService::signup(body.into_inner(), &mut pool.get().unwrap()) {
+    match UserService::signup(body.into_inner(), mailer.get_ref(), &mut pool.get().unwrap()) {
         Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
         Err(e) => Err(e),
     }
@@ -50,3 +58,105 @@ async fn login(
         Err(e) => Err(e),
     }
 }
+
+#[derive(Deserialize, Validate)]
+pub struct RefreshTokenPayload {
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+}
+
+#[post("/refresh")]
+async fn refresh(
+    body: web::Json<RefreshTokenPayload>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match UserService::refresh(body.into_inner(), &mut pool.get().unwrap()) {
+        Ok(token_res) => Ok(HttpResponse::Ok().json(ResponseBody::new(token_res))),
+        Err(e) => Err(e),
+    }
+}
+
+#[post("/logout")]
+async fn logout(auth: UserAuth, pool: web::Data<Pool>) -> Result<HttpResponse, ServiceError> {
+    match UserService::logout(&auth.session_id, &mut pool.get().unwrap()) {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct PasswordResetRequestPayload {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[post("/password-reset/request")]
+async fn request_password_reset(
+    body: web::Json<PasswordResetRequestPayload>,
+    mailer: web::Data<dyn MailTransport>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        PasswordResetService::request(
+            body.into_inner(),
+            mailer.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Accepted().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct PasswordResetConfirmPayload {
+    #[validate(length(min = 1))]
+    pub token: String,
+    #[validate(length(min = 6))]
+    pub password: String,
+}
+
+#[post("/password-reset/confirm")]
+async fn confirm_password_reset(
+    body: web::Json<PasswordResetConfirmPayload>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match PasswordResetService::confirm(body.into_inner(), &mut pool.get().unwrap()) {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+#[get("/verify")]
+async fn verify_email(
+    query: web::Query<VerifyEmailQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match EmailVerificationService::verify(&query.token, &mut pool.get().unwrap()) {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[post("/verify/resend")]
+async fn resend_verification_email(
+    auth: UserAuth,
+    mailer: web::Data<dyn MailTransport>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        EmailVerificationService::resend(
+            &auth.user_id,
+            mailer.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Accepted().finish()),
+        Err(e) => Err(e),
+    }
+}
@@ -3,3 +3,7 @@ pub mod address;
 pub mod store;
 pub mod product;
 pub mod shopping_cart;
+pub mod order;
+pub mod promo_code;
+pub mod payment_method;
+pub mod shipping;
\ No newline at end of file
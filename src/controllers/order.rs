@@ -0,0 +1,160 @@
+use actix_web::{ web, get, post, put, HttpResponse };
+use chrono::NaiveDate;
+use serde::Deserialize;
+use validator::Validate;
+use crate::{
+    db::{ Pool, order::OrderService },
+    error::ServiceError,
+    middleware::auth::{ CustomerAuth, StoreAdminAuth },
+    models::{ response::ResponseBody, order::OrderStatus },
+};
+
+#[derive(Deserialize)]
+pub struct UpdateOrderStatusBody {
+    pub status: OrderStatus,
+}
+
+#[put("/orders/{id}/status")]
+async fn update_order_status(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<UpdateOrderStatusBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        OrderService::update_status(
+            &path.into_inner(),
+            body.into_inner().status,
+            &store_id,
+            &user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/orders/{id}/status-history")]
+async fn list_order_status_history(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        OrderService::list_status_history(
+            &path.into_inner(),
+            &store_id,
+            &user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(history) => Ok(HttpResponse::Ok().json(ResponseBody::new(history))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("")]
+async fn list_orders(
+    auth: CustomerAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match OrderService::list_by_customer(&auth.user_id, &mut pool.get().unwrap()) {
+        Ok(orders) => Ok(HttpResponse::Ok().json(ResponseBody::new(orders))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/{id}")]
+async fn find_order(
+    auth: CustomerAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        OrderService::find_by_customer(
+            &path.into_inner(),
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(order) => Ok(HttpResponse::Ok().json(ResponseBody::new(order))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ListStoreOrdersQuery {
+    pub status: Option<OrderStatus>,
+    pub from_date: Option<NaiveDate>,
+    pub to_date: Option<NaiveDate>,
+    #[validate(length(min = 1, max = 60))]
+    pub customer_email: Option<String>,
+    #[validate(range(min = 0.0))]
+    pub min_total: Option<f64>,
+    #[validate(range(min = 0.0))]
+    pub max_total: Option<f64>,
+    #[validate(range(min = 1))]
+    pub page: Option<i64>,
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
+
+#[get("/orders")]
+async fn list_store_orders(
+    auth: StoreAdminAuth,
+    query: web::Query<ListStoreOrdersQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        OrderService::list_by_store(
+            query.into_inner(),
+            &store_id,
+            &user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(orders) => Ok(HttpResponse::Ok().json(ResponseBody::new(orders))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ShipmentBody {
+    pub order_id: i32,
+    #[validate(length(min = 1, max = 100))]
+    pub tracking_number: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct MarkShippedBody {
+    #[validate]
+    pub orders: Vec<ShipmentBody>,
+}
+
+#[post("/orders/mark-shipped")]
+async fn mark_orders_shipped(
+    auth: StoreAdminAuth,
+    body: web::Json<MarkShippedBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        OrderService::mark_shipped(
+            body.into_inner(),
+            &store_id,
+            &user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,96 @@
+use actix_web::{ web, get, post, put, delete, HttpResponse };
+use serde::Deserialize;
+use validator::Validate;
+use crate::{
+    db::{ Pool, payment_method::PaymentMethodService },
+    error::ServiceError,
+    middleware::auth::StoreAdminAuth,
+    models::response::ResponseBody,
+};
+
+#[derive(Deserialize, Validate)]
+pub struct PaymentMethodBody {
+    #[validate(length(min = 2, max = 60))]
+    pub name: String,
+    #[serde(default)]
+    pub inactive: bool,
+}
+
+#[post("/payment-methods")]
+async fn create_payment_method(
+    auth: StoreAdminAuth,
+    body: web::Json<PaymentMethodBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        PaymentMethodService::create(body.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/payment-methods")]
+async fn list_payment_methods(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match PaymentMethodService::list(&user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[put("/payment-methods/{id}")]
+async fn edit_payment_method(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<PaymentMethodBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        PaymentMethodService::edit(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/payment-methods/{id}")]
+async fn delete_payment_method(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        PaymentMethodService::delete(&path.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/{store_id}/payment-methods")]
+async fn list_active_payment_methods(
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match PaymentMethodService::list_active(&path.into_inner(), &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
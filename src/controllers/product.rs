@@ -1,27 +1,49 @@
-use actix_web::{ web, post, get, HttpResponse };
-use serde::Deserialize;
+use actix_multipart::Multipart;
+use actix_web::{ web, post, get, put, patch, delete, HttpResponse };
+use chrono::NaiveDateTime;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
 use validator::Validate;
 
 use crate::{
-    db::{ Pool, product::ProductService },
+    db::{
+        Pool,
+        product::ProductService,
+        product_discount::ProductDiscountService,
+        product_import::ProductImportService,
+        product_search::ProductSearchService,
+        product_item::{ ProductItemService, MAX_ITEM_IMAGES },
+    },
     error::ServiceError,
-    middleware::auth::AuthMiddleware,
-    models::{ response::ResponseBody, user::UserType },
+    middleware::auth::StoreAdminAuth,
+    models::response::ResponseBody,
+    storage::FileStorage,
+    utils::{ upload::read_files, slug::REGEX_SLUG },
 };
 
+lazy_static! {
+    // `;` and `:` separate options from their values in the CSV `variant` column
+    pub static ref REGEX_OPTION_TEXT: Regex = Regex::new(r"^[^;:]*$").unwrap();
+}
+
 #[derive(Deserialize, Validate)]
 pub struct CreateCategoryBody {
     #[validate(length(min = 2, max = 60))]
     pub name: String,
+    /// Generated from the name when missing.
+    #[validate(length(min = 2, max = 60), regex = "REGEX_SLUG")]
+    pub slug: Option<String>,
+    pub parent_id: Option<i32>,
 }
 
 #[post("/categories")]
 async fn create_product_category(
-    auth: AuthMiddleware,
+    auth: StoreAdminAuth,
     body: web::Json<CreateCategoryBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let (user_id, store_id) = (auth.user.sub.parse().unwrap(), auth.user.managed_store_id.unwrap());
+    let StoreAdminAuth { user_id, store_id } = auth;
 
     match
         ProductService::create_category(
@@ -37,19 +59,82 @@ async fn create_product_category(
 }
 
 #[derive(Deserialize, Validate)]
-pub struct CreateVariantBody {
+pub struct EditCategoryBody {
     #[validate(length(min = 2, max = 60))]
     pub name: String,
+    /// Kept as is when missing.
+    #[validate(length(min = 2, max = 60), regex = "REGEX_SLUG")]
+    pub slug: Option<String>,
+}
+
+#[put("/categories/{id}")]
+async fn edit_product_category(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<EditCategoryBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::edit_category(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReassignQuery {
+    /// Row that takes over the references of the deleted one.
+    pub reassign_to: Option<i32>,
+}
+
+#[delete("/categories/{id}")]
+async fn delete_product_category(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    query: web::Query<ReassignQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::delete_category(
+            &path.into_inner(),
+            query.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Adds `value` to the option type called `name`, creating the option type if needed.
+#[derive(Deserialize, Validate)]
+pub struct CreateVariantBody {
+    #[validate(length(min = 2, max = 60), regex = "REGEX_OPTION_TEXT")]
+    pub name: String,
+    #[validate(length(min = 1, max = 60), regex = "REGEX_OPTION_TEXT")]
     pub value: String,
 }
 
 #[post("/variants")]
 async fn create_product_variant(
-    auth: AuthMiddleware,
+    auth: StoreAdminAuth,
     body: web::Json<CreateVariantBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let (user_id, store_id) = (auth.user.sub.parse().unwrap(), auth.user.managed_store_id.unwrap());
+    let StoreAdminAuth { user_id, store_id } = auth;
 
     match
         ProductService::create_variant(
@@ -64,6 +149,164 @@ async fn create_product_variant(
     }
 }
 
+#[put("/variants/{id}")]
+async fn edit_product_variant(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<OptionValueBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::edit_variant(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/variants/{id}")]
+async fn delete_product_variant(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    query: web::Query<ReassignQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::delete_variant(
+            &path.into_inner(),
+            query.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CreateOptionBody {
+    #[validate(length(min = 2, max = 60), regex = "REGEX_OPTION_TEXT")]
+    pub name: String,
+    #[validate(length(max = 100))]
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[post("/options")]
+async fn create_product_option(
+    auth: StoreAdminAuth,
+    body: web::Json<CreateOptionBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::create_option(body.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/options")]
+async fn list_options(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ProductService::list_options(&store_id, &user_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditOptionBody {
+    #[validate(length(min = 2, max = 60), regex = "REGEX_OPTION_TEXT")]
+    pub name: String,
+}
+
+#[put("/options/{id}")]
+async fn edit_product_option(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<EditOptionBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::edit_option(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/options/{id}")]
+async fn delete_product_option(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::delete_option(&path.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct OptionValueBody {
+    #[validate(length(min = 1, max = 60), regex = "REGEX_OPTION_TEXT")]
+    pub value: String,
+}
+
+#[post("/options/{id}/values")]
+async fn create_option_value(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<OptionValueBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::create_option_value(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
 #[derive(Deserialize, Validate)]
 pub struct ProductDataBody {
     pub description: String,
@@ -71,7 +314,11 @@ pub struct ProductDataBody {
     pub sku: String,
     pub price: f32,
     pub stock: i32,
-    pub variant_id: Option<i32>,
+    /// Option values of the item, at most one per option type.
+    #[serde(default)]
+    pub variant_ids: Vec<i32>,
+    #[validate(range(min = 0))]
+    pub weight_grams: Option<i32>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -85,11 +332,11 @@ pub struct CreateProductBody {
 
 #[post("")]
 async fn create_product(
-    auth: AuthMiddleware,
+    auth: StoreAdminAuth,
     body: web::Json<CreateProductBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let (user_id, store_id) = (auth.user.sub.parse().unwrap(), auth.user.managed_store_id.unwrap());
+    let StoreAdminAuth { user_id, store_id } = auth;
 
     match ProductService::create(body.into_inner(), &user_id, &store_id, &mut pool.get().unwrap()) {
         Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
@@ -97,23 +344,94 @@ async fn create_product(
     }
 }
 
-#[get("/variants")]
-async fn list_variants(
-    auth: AuthMiddleware,
+/// One product item per line of the import/export CSV. Items are matched by `sku`, new
+/// ones join the store's product called `name`; only items of products the store doesn't
+/// have yet may leave the sku out.
+/// `category` is a category slug or name and `variant` lists option values as
+/// `Size: M; Color: Red`.
+#[derive(Deserialize, Serialize, Validate)]
+pub struct ProductCsvRow {
+    #[validate(length(min = 2, max = 60))]
+    pub name: String,
+    #[validate(length(min = 1, max = 60))]
+    pub category: String,
+    #[validate(length(min = 1, max = 60))]
+    pub sku: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// Kept as text so a malformed price is reported like any other row error.
+    pub price: String,
+    #[validate(range(min = 0))]
+    pub stock: i32,
+    #[validate(length(max = 400))]
+    pub variant: Option<String>,
+    #[validate(length(max = 2000))]
+    pub image: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    /// Only checks the file, reporting what would be created and updated.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[post("/import")]
+async fn import_products(
+    auth: StoreAdminAuth,
+    query: web::Query<ImportQuery>,
+    payload: Multipart,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    let file = read_files(payload, "file", 1).await?.remove(0);
+    let dry_run = query.dry_run;
+
+    // parsing and checking every row against the database would otherwise stall this worker
+    let result = web::block(move || {
+        ProductImportService::import(file, dry_run, &user_id, &store_id, &mut pool.get().unwrap())
+    }).await?;
+
+    match result {
+        Ok(report) if report.errors.is_empty() =>
+            Ok(HttpResponse::Ok().json(ResponseBody::new(report))),
+        Ok(report) => Ok(HttpResponse::UnprocessableEntity().json(ResponseBody::new(report))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/export")]
+async fn export_products(
+    auth: StoreAdminAuth,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let (user_type, store_id) = (
-        UserType::from_i32(auth.user.type_),
-        auth.user.managed_store_id.unwrap(),
-    );
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    let result = web::block(move || {
+        ProductImportService::export(&user_id, &store_id, &mut pool.get().unwrap())
+    }).await?;
 
-    if user_type == UserType::CUSTOMER {
-        return Err(ServiceError::Forbidden {
-            error_message: "User without permissions".to_string(),
-        });
+    match result {
+        Ok(csv) =>
+            Ok(
+                HttpResponse::Ok()
+                    .content_type("text/csv; charset=utf-8")
+                    .insert_header(("Content-Disposition", "attachment; filename=\"products.csv\""))
+                    .body(csv)
+            ),
+        Err(e) => Err(e),
     }
+}
 
-    match ProductService::list_variants(&store_id, &mut pool.get().unwrap()) {
+#[get("/variants")]
+async fn list_variants(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ProductService::list_variants(&store_id, &user_id, &mut pool.get().unwrap()) {
         Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
         Err(e) => Err(e),
     }
@@ -121,22 +439,533 @@ async fn list_variants(
 
 #[get("/categories")]
 async fn list_categories(
-    auth: AuthMiddleware,
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ProductService::list_categories(&store_id, &user_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MoveCategoryBody {
+    /// New parent of the category, none turns it into a root category.
+    pub parent_id: Option<i32>,
+}
+
+#[put("/categories/{id}/parent")]
+async fn move_product_category(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<MoveCategoryBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    let (user_type, store_id) = (
-        UserType::from_i32(auth.user.type_),
-        auth.user.managed_store_id.unwrap(),
-    );
+    let StoreAdminAuth { user_id, store_id } = auth;
 
-    if user_type == UserType::CUSTOMER {
-        return Err(ServiceError::Forbidden {
-            error_message: "User without permissions".to_string(),
-        });
+    match
+        ProductService::move_category(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
     }
+}
 
-    match ProductService::list_categories(&store_id, &mut pool.get().unwrap()) {
+#[get("/{store_id}/categories")]
+async fn list_store_categories(
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match ProductService::category_tree(&path.into_inner(), &mut pool.get().unwrap()) {
         Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
         Err(e) => Err(e),
     }
 }
+
+#[derive(Deserialize, Validate)]
+pub struct DiscountBody {
+    #[validate(range(min = 0.01, max = 100.0))]
+    pub percentual: f32,
+    pub expires_at: NaiveDateTime,
+}
+
+#[post("/discounts")]
+async fn create_product_discount(
+    auth: StoreAdminAuth,
+    body: web::Json<DiscountBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductDiscountService::create(
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/discounts")]
+async fn list_product_discounts(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ProductDiscountService::list(&user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[put("/discounts/{id}")]
+async fn edit_product_discount(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<DiscountBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductDiscountService::edit(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/discounts/{id}")]
+async fn delete_product_discount(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductDiscountService::delete(
+            &path.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AttachDiscountBody {
+    pub discount_id: Option<i32>,
+}
+
+#[put("/items/{id}/discount")]
+async fn attach_product_discount(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<AttachDiscountBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductDiscountService::attach_to_item(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CatalogQuery {
+    pub category_id: Option<i32>,
+    #[validate(length(max = 60))]
+    pub category_slug: Option<String>,
+    /// Also list products of the category's subcategories, at any depth.
+    pub include_descendants: Option<bool>,
+    #[validate(range(min = 1))]
+    pub page: Option<i64>,
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
+
+#[get("/{store_id}/products")]
+async fn list_store_products(
+    path: web::Path<i32>,
+    query: web::Query<CatalogQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        ProductService::list_catalog(
+            &path.into_inner(),
+            query.into_inner(),
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(products) => Ok(HttpResponse::Ok().json(ResponseBody::new(products))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ProductSearchQuery {
+    #[validate(length(min = 1, max = 100))]
+    pub q: String,
+    pub category_id: Option<i32>,
+    #[validate(range(min = 0.0))]
+    pub min_price: Option<f64>,
+    #[validate(range(min = 0.0))]
+    pub max_price: Option<f64>,
+    #[validate(length(max = 60))]
+    pub variant_name: Option<String>,
+    #[validate(length(max = 60))]
+    pub variant_value: Option<String>,
+    pub in_stock: Option<bool>,
+    #[validate(range(min = 1))]
+    pub page: Option<i64>,
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
+
+#[get("/{store_id}/products/search")]
+async fn search_store_products(
+    path: web::Path<i32>,
+    query: web::Query<ProductSearchQuery>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        ProductSearchService::search(
+            &path.into_inner(),
+            query.into_inner(),
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(results) => Ok(HttpResponse::Ok().json(ResponseBody::new(results))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/{id}")]
+async fn find_product(
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match ProductService::find_catalog(&path.into_inner(), &mut pool.get().unwrap()) {
+        Ok(product) => Ok(HttpResponse::Ok().json(ResponseBody::new(product))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditProductBody {
+    #[validate(length(min = 2, max = 60))]
+    pub name: String,
+    pub category_id: i32,
+}
+
+#[put("/{id}")]
+async fn edit_product(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<EditProductBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::edit(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/{id}")]
+async fn delete_product(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::set_deleted(
+            &path.into_inner(),
+            true,
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[post("/{id}/restore")]
+async fn restore_product(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductService::set_deleted(
+            &path.into_inner(),
+            false,
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditProductItemBody {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub sku: Option<String>,
+    #[validate(range(min = 0.0))]
+    pub price: Option<f32>,
+    #[validate(range(min = 0))]
+    pub stock: Option<i32>,
+    /// Replaces the item's option values when given, an empty list clears them.
+    pub variant_ids: Option<Vec<i32>>,
+    #[validate(range(min = 0))]
+    pub weight_grams: Option<i32>,
+}
+
+#[patch("/{id}/items/{item_id}")]
+async fn edit_product_item(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32)>,
+    body: web::Json<EditProductItemBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id) = path.into_inner();
+
+    match
+        ProductItemService::edit(
+            &item_id,
+            &product_id,
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct GenerateItemsBody {
+    /// Selected option values. Every combination taking one value per option type
+    /// becomes an item.
+    #[validate(length(min = 1, max = 100))]
+    pub variant_ids: Vec<i32>,
+    #[validate(range(min = 0.0))]
+    pub price: f32,
+    #[validate(range(min = 0))]
+    pub stock: i32,
+    #[validate(range(min = 0))]
+    pub weight_grams: Option<i32>,
+    #[validate(length(min = 1, max = 40))]
+    pub sku_prefix: Option<String>,
+}
+
+#[post("/{id}/items/generate")]
+async fn generate_product_items(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<GenerateItemsBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ProductItemService::generate(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(generated) => Ok(HttpResponse::Created().json(ResponseBody::new(generated))),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/{id}/items/{item_id}")]
+async fn delete_product_item(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32)>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id) = path.into_inner();
+
+    match
+        ProductItemService::set_deleted(
+            &item_id,
+            &product_id,
+            true,
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[post("/{id}/items/{item_id}/restore")]
+async fn restore_product_item(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32)>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id) = path.into_inner();
+
+    match
+        ProductItemService::set_deleted(
+            &item_id,
+            &product_id,
+            false,
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[post("/{id}/items/{item_id}/images")]
+async fn upload_product_item_images(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32)>,
+    payload: Multipart,
+    storage: web::Data<dyn FileStorage>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id) = path.into_inner();
+
+    let files = read_files(payload, "image", MAX_ITEM_IMAGES).await?;
+
+    // decoding, resizing and storing the images would otherwise stall this worker
+    let images = web::block(move || {
+        ProductItemService::add_images(
+            &item_id,
+            &product_id,
+            files,
+            &user_id,
+            &store_id,
+            storage.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    }).await??;
+
+    Ok(HttpResponse::Created().json(ResponseBody::new(images)))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ReorderImagesBody {
+    /// Every image of the item, in the order they should be shown.
+    #[validate(length(min = 1, max = 12))]
+    pub image_ids: Vec<i32>,
+}
+
+#[put("/{id}/items/{item_id}/images/order")]
+async fn reorder_product_item_images(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32)>,
+    body: web::Json<ReorderImagesBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id) = path.into_inner();
+
+    match
+        ProductItemService::reorder_images(
+            &item_id,
+            &product_id,
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/{id}/items/{item_id}/images/{image_id}")]
+async fn delete_product_item_image(
+    auth: StoreAdminAuth,
+    path: web::Path<(i32, i32, i32)>,
+    storage: web::Data<dyn FileStorage>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+    let (product_id, item_id, image_id) = path.into_inner();
+
+    web::block(move || {
+        ProductItemService::delete_image(
+            &item_id,
+            &product_id,
+            &image_id,
+            &user_id,
+            &store_id,
+            storage.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    }).await??;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,149 @@
+use actix_web::{ web, post, get, put, delete, HttpResponse };
+use chrono::NaiveDateTime;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+use crate::{
+    db::{ Pool, promo_code::PromoCodeService },
+    error::ServiceError,
+    middleware::auth::{ CustomerAuth, StoreAdminAuth },
+    models::{ response::ResponseBody, promo_code::DiscountType },
+};
+
+lazy_static! {
+    static ref REGEX_PROMO_CODE: Regex = Regex::new(r"^[A-Za-z0-9_-]{3,30}$").unwrap();
+}
+
+#[derive(Deserialize, Validate)]
+pub struct PromoCodeBody {
+    #[validate(regex = "REGEX_PROMO_CODE")]
+    pub code: String,
+    pub discount_type: DiscountType,
+    #[validate(range(min = 0.01))]
+    pub amount: f64,
+    #[validate(range(min = 0.0))]
+    pub min_cart_total: Option<f64>,
+    #[validate(range(min = 1))]
+    pub max_uses: Option<i32>,
+    #[validate(range(min = 1))]
+    pub max_uses_per_customer: Option<i32>,
+    #[serde(default)]
+    pub category_ids: Vec<i32>,
+    #[serde(default)]
+    pub product_ids: Vec<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub inactive: bool,
+}
+
+#[post("/promo-codes")]
+async fn create_promo_code(
+    auth: StoreAdminAuth,
+    body: web::Json<PromoCodeBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match PromoCodeService::create(body.into_inner(), &user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/promo-codes")]
+async fn list_promo_codes(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match PromoCodeService::list(&user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[put("/promo-codes/{id}")]
+async fn edit_promo_code(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<PromoCodeBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        PromoCodeService::edit(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/promo-codes/{id}")]
+async fn delete_promo_code(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        PromoCodeService::delete(&path.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ApplyPromoCodeBody {
+    #[validate(regex = "REGEX_PROMO_CODE")]
+    pub code: String,
+}
+
+#[post("/{id}/promo-code")]
+async fn apply_promo_code(
+    auth: CustomerAuth,
+    path: web::Path<i32>,
+    body: web::Json<ApplyPromoCodeBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        PromoCodeService::apply_to_cart(
+            &path.into_inner(),
+            body.into_inner(),
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(applied) => Ok(HttpResponse::Ok().json(ResponseBody::new(applied))),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/{id}/promo-code")]
+async fn remove_promo_code(
+    auth: CustomerAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        PromoCodeService::remove_from_cart(
+            &path.into_inner(),
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,111 @@
+use actix_web::{ web, get, post, put, delete, HttpResponse };
+use serde::Deserialize;
+use validator::Validate;
+use crate::{
+    db::{ Pool, shipping::ShippingService },
+    error::ServiceError,
+    middleware::auth::StoreAdminAuth,
+    models::{ response::ResponseBody, shipping::ShippingRateType },
+};
+
+#[derive(Deserialize, Validate)]
+pub struct ShippingRateBody {
+    pub rate_type: ShippingRateType,
+    /// Destination country as stored in `user_address.country`, none matches every country.
+    #[validate(length(min = 2, max = 60))]
+    pub country: Option<String>,
+    #[validate(range(min = 0.0, max = 9999999999.99))]
+    pub min_value: Option<f64>,
+    #[validate(range(min = 0.0, max = 9999999999.99))]
+    pub max_value: Option<f64>,
+    #[validate(range(min = 0.0, max = 99999999.99))]
+    pub price: f64,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ShippingMethodBody {
+    #[validate(length(min = 2, max = 60))]
+    pub name: String,
+    #[serde(default)]
+    pub inactive: bool,
+    #[validate]
+    #[serde(default)]
+    pub rates: Vec<ShippingRateBody>,
+}
+
+#[post("/shipping-methods")]
+async fn create_shipping_method(
+    auth: StoreAdminAuth,
+    body: web::Json<ShippingMethodBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ShippingService::create(body.into_inner(), &user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/shipping-methods")]
+async fn list_shipping_methods(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match ShippingService::list(&user_id, &store_id, &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
+
+#[put("/shipping-methods/{id}")]
+async fn edit_shipping_method(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<ShippingMethodBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ShippingService::edit(
+            &path.into_inner(),
+            body.into_inner(),
+            &user_id,
+            &store_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/shipping-methods/{id}")]
+async fn delete_shipping_method(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        ShippingService::delete(&path.into_inner(), &user_id, &store_id, &mut pool.get().unwrap())
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/{store_id}/shipping-methods")]
+async fn list_active_shipping_methods(
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match ShippingService::list_active(&path.into_inner(), &mut pool.get().unwrap()) {
+        Ok(values) => Ok(HttpResponse::Ok().json(ResponseBody::new(values))),
+        Err(e) => Err(e),
+    }
+}
@@ -3,28 +3,29 @@ use serde::Deserialize;
 use validator::Validate;
 use crate::{
     error::ServiceError,
-    middleware::auth::AuthMiddleware,
+    middleware::auth::CustomerAuth,
     db::{ Pool, shopping_cart::ShoppingCartService, order_item::OrderItemService },
     models::response::ResponseBody,
 };
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct NewShoppingCartBody {
     pub store_id: i32,
     pub product_item_id: i32,
+    #[validate(range(min = 1))]
     pub quantity: i32,
 }
 
 #[post("")]
 async fn create_shopping_cart(
-    auth: AuthMiddleware,
+    auth: CustomerAuth,
     body: web::Json<NewShoppingCartBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
     match
         ShoppingCartService::create(
             body.into_inner(),
-            auth.user.sub.parse().unwrap(),
+            auth.user_id,
             &mut pool.get().unwrap()
         )
     {
@@ -43,12 +44,19 @@ pub struct EditShoppingCartBody {
 
 #[put("/{id}")]
 async fn edit_shopping_cart(
-    _: AuthMiddleware,
+    auth: CustomerAuth,
     path: web::Path<i32>,
     body: web::Json<EditShoppingCartBody>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    match ShoppingCartService::edit(body.into_inner(), path.into_inner(), &mut pool.get().unwrap()) {
+    match
+        ShoppingCartService::edit(
+            body.into_inner(),
+            path.into_inner(),
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
         Ok(_) => Ok(HttpResponse::Ok().finish()),
         Err(e) => Err(e),
     }
@@ -56,7 +64,7 @@ async fn edit_shopping_cart(
 
 #[delete("/{id}")]
 async fn delete_shopping_cart(
-    _: AuthMiddleware,
+    auth: CustomerAuth,
     path: web::Path<i32>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
@@ -64,7 +72,7 @@ async fn delete_shopping_cart(
 
     let mut conn = pool.get().unwrap();
 
-    ShoppingCartService::find(&shopping_cart_id, &mut conn)?;
+    ShoppingCartService::find_owned(&shopping_cart_id, &auth.user_id, &mut conn)?;
 
     let order_items = OrderItemService::find_by_shopping_cart(&shopping_cart_id, &mut conn);
 
@@ -78,7 +86,60 @@ async fn delete_shopping_cart(
         OrderItemService::delete(order_items_ids_to_delete, &mut conn)?;
     }
 
-    ShoppingCartService::delete(&shopping_cart_id, &mut conn)?;
+    ShoppingCartService::delete(&shopping_cart_id, &auth.user_id, &mut conn)?;
     
     Ok(HttpResponse::Ok().finish())
 }
+
+#[derive(Deserialize)]
+pub struct CheckoutBody {
+    pub payment_method_id: i32,
+    pub shipping_method_id: i32,
+    pub user_address_id: i32,
+}
+
+#[post("/{id}/checkout")]
+async fn checkout_shopping_cart(
+    auth: CustomerAuth,
+    path: web::Path<i32>,
+    body: web::Json<CheckoutBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        ShoppingCartService::checkout(
+            body.into_inner(),
+            path.into_inner(),
+            auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ShippingQuoteBody {
+    pub shipping_method_id: i32,
+    pub user_address_id: i32,
+}
+
+#[post("/{id}/shipping-quote")]
+async fn quote_shipping(
+    auth: CustomerAuth,
+    path: web::Path<i32>,
+    body: web::Json<ShippingQuoteBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        ShoppingCartService::quote_shipping(
+            body.into_inner(),
+            &path.into_inner(),
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(quote) => Ok(HttpResponse::Ok().json(ResponseBody::new(quote))),
+        Err(e) => Err(e),
+    }
+}
@@ -1,13 +1,23 @@
-use actix_web::{ web, post, HttpResponse };
+use actix_multipart::Multipart;
+use actix_web::{ web, get, post, put, delete, HttpResponse };
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::Deserialize;
 use validator::Validate;
 use crate::{
     db::{ Pool, store::StoreService },
     error::ServiceError,
-    models::response::ResponseBody,
-    middleware::auth::AuthMiddleware,
+    mail::MailTransport,
+    models::{ response::ResponseBody, role::StoreRole },
+    middleware::auth::{ StoreAdminAuth, OptionalAuth },
+    storage::FileStorage,
+    utils::{ upload::read_files, slug::REGEX_SLUG },
 };
 
+lazy_static! {
+    static ref REGEX_CURRENCY: Regex = Regex::new(r"^[A-Z]{3}$").unwrap();
+}
+
 #[derive(Deserialize, Validate)]
 pub struct NewStorePayload {
     #[validate(length(min = 2, max = 60))]
@@ -26,22 +36,47 @@ pub struct NewStorePayload {
 #[post("")]
 async fn create_store(
     body: web::Json<NewStorePayload>,
+    mailer: web::Data<dyn MailTransport>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
-    match StoreService::create(body.into_inner(), &mut pool.get().unwrap()) {
+    match StoreService::create(body.into_inner(), mailer.get_ref(), &mut pool.get().unwrap()) {
         Ok(id) => Ok(HttpResponse::Created().json(ResponseBody::new(id))),
         Err(e) => Err(e),
     }
 }
 
+#[derive(Deserialize, Validate)]
+pub struct StoreInviteBody {
+    pub role: Option<StoreRole>,
+    #[validate(email)]
+    pub email: Option<String>,
+    #[validate(range(min = 1, max = 100))]
+    pub max_uses: Option<i32>,
+    #[validate(range(min = 1, max = 720))]
+    pub expires_in_hours: Option<i32>,
+}
+
 #[post("/store-invite")]
 async fn create_store_invite(
-    auth: AuthMiddleware,
+    auth: StoreAdminAuth,
+    body: Option<web::Json<StoreInviteBody>>,
+    mailer: web::Data<dyn MailTransport>,
     pool: web::Data<Pool>
 ) -> Result<HttpResponse, ServiceError> {
+    // an empty body creates a single use manager invite
+    let payload = body.map(|body| body.into_inner()).unwrap_or(StoreInviteBody {
+        role: None,
+        email: None,
+        max_uses: None,
+        expires_in_hours: None,
+    });
+
     match
         StoreService::create_store_invite(
-            &auth.user.managed_store_id.unwrap(),
+            payload,
+            &auth.store_id,
+            &auth.user_id,
+            mailer.get_ref(),
             &mut pool.get().unwrap()
         )
     {
@@ -49,3 +84,179 @@ async fn create_store_invite(
         Err(e) => Err(e),
     }
 }
+
+#[get("/store-invite")]
+async fn list_store_invites(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match StoreService::list_store_invites(&auth.store_id, &auth.user_id, &mut pool.get().unwrap()) {
+        Ok(invites) => Ok(HttpResponse::Ok().json(ResponseBody::new(invites))),
+        Err(e) => Err(e),
+    }
+}
+
+#[delete("/store-invite/{id}")]
+async fn revoke_store_invite(
+    auth: StoreAdminAuth,
+    path: web::Path<String>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        StoreService::revoke_store_invite(
+            &path.into_inner(),
+            &auth.store_id,
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct StoreRoleBody {
+    pub role: StoreRole,
+}
+
+#[put("/admins/{id}/role")]
+async fn update_admin_role(
+    auth: StoreAdminAuth,
+    path: web::Path<i32>,
+    body: web::Json<StoreRoleBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let StoreAdminAuth { user_id, store_id } = auth;
+
+    match
+        StoreService::update_admin_role(
+            body.into_inner(),
+            &path.into_inner(),
+            &store_id,
+            &user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditStoreBody {
+    #[validate(length(min = 2, max = 60))]
+    pub name: String,
+    pub logo_url: Option<String>,
+    #[validate(length(min = 2, max = 60), regex = "REGEX_SLUG")]
+    pub slug: String,
+    #[validate(regex = "REGEX_CURRENCY")]
+    pub currency: String,
+    #[validate(email)]
+    pub contact_email: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+}
+
+#[put("")]
+async fn edit_store(
+    auth: StoreAdminAuth,
+    body: web::Json<EditStoreBody>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match
+        StoreService::edit(
+            body.into_inner(),
+            &auth.store_id,
+            &auth.user_id,
+            &mut pool.get().unwrap()
+        )
+    {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Err(e),
+    }
+}
+
+#[put("/logo")]
+async fn upload_store_logo(
+    auth: StoreAdminAuth,
+    payload: Multipart,
+    storage: web::Data<dyn FileStorage>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let mut files = read_files(payload, "logo", 1).await?;
+
+    // decoding, resizing and storing the logo would otherwise stall this worker
+    let logo = web::block(move || {
+        StoreService::set_logo(
+            files.remove(0),
+            &auth.store_id,
+            &auth.user_id,
+            storage.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    }).await??;
+
+    Ok(HttpResponse::Ok().json(ResponseBody::new(logo)))
+}
+
+#[delete("/logo")]
+async fn delete_store_logo(
+    auth: StoreAdminAuth,
+    storage: web::Data<dyn FileStorage>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    web::block(move || {
+        StoreService::remove_logo(
+            &auth.store_id,
+            &auth.user_id,
+            storage.get_ref(),
+            &mut pool.get().unwrap()
+        )
+    }).await??;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/admins")]
+async fn list_store_admins(
+    auth: StoreAdminAuth,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    match StoreService::list_admins(&auth.store_id, &auth.user_id, &mut pool.get().unwrap()) {
+        Ok(admins) => Ok(HttpResponse::Ok().json(ResponseBody::new(admins))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/by-slug/{slug}")]
+async fn find_store_by_slug(
+    auth: OptionalAuth,
+    path: web::Path<String>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let mut conn = pool.get().unwrap();
+
+    let store = StoreService::find_by_slug(&path.into_inner(), &mut conn)?;
+
+    match StoreService::profile(store, auth.0.map(|user| user.user_id), &mut conn) {
+        Ok(profile) => Ok(HttpResponse::Ok().json(ResponseBody::new(profile))),
+        Err(e) => Err(e),
+    }
+}
+
+#[get("/{id}")]
+async fn find_store(
+    auth: OptionalAuth,
+    path: web::Path<i32>,
+    pool: web::Data<Pool>
+) -> Result<HttpResponse, ServiceError> {
+    let mut conn = pool.get().unwrap();
+
+    let store = StoreService::find(&path.into_inner(), &mut conn)?;
+
+    match StoreService::profile(store, auth.0.map(|user| user.user_id), &mut conn) {
+        Ok(profile) => Ok(HttpResponse::Ok().json(ResponseBody::new(profile))),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,165 @@
+use diesel::{
+    prelude::*,
+    dsl::{ now, IntervalDsl },
+    pg::data_types::PgInterval,
+    sql_query,
+    sql_types::{ Integer, Text },
+    Connection as _,
+};
+use log::error;
+use crate::{
+    error::ServiceError,
+    mail::{ Mail, MailTransport },
+    models::{ email_verification::EmailVerificationToken, user::User },
+    utils::token::{ generate_token, hash_token },
+};
+use super::{ Connection, user::UserService };
+
+const DEFAULT_EMAIL_VERIFICATION_HOURS: i32 = 24;
+
+pub struct EmailVerificationService;
+
+impl EmailVerificationService {
+    fn token_lifetime() -> PgInterval {
+        std::env::var("EMAIL_VERIFICATION_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse().ok())
+            .unwrap_or(DEFAULT_EMAIL_VERIFICATION_HOURS)
+            .hours()
+    }
+
+    fn verify_url(token: &str) -> String {
+        let app_url = std::env
+            ::var("APP_URL")
+            .unwrap_or_else(|_| format!("http://{}", std::env::var("BIND_ADDRESS").unwrap_or_default()));
+
+        format!("{}/auth/verify?token={}", app_url.trim_end_matches('/'), token)
+    }
+
+    /// Whether unverified users are kept out of checkout and store management, set
+    /// through `REQUIRE_EMAIL_VERIFICATION`.
+    pub fn verification_required() -> bool {
+        std::env::var("REQUIRE_EMAIL_VERIFICATION").is_ok_and(|required| required == "true")
+    }
+
+    pub fn check_verified(user_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+        if !Self::verification_required() {
+            return Ok(());
+        }
+
+        let existing_user = UserService::find(user_id, conn)?;
+
+        match existing_user.email_verified_at {
+            Some(_) => Ok(()),
+            None =>
+                Err(ServiceError::Forbidden {
+                    error_message: "Email address is not verified".to_string(),
+                }),
+        }
+    }
+
+    /// Stores a new verification token for the user and returns it in plain form.
+    pub fn create_token(user_id: &i32, conn: &mut Connection) -> Result<String, ServiceError> {
+        use crate::schema::email_verification_token::dsl;
+
+        let verification_token = generate_token();
+
+        diesel
+            ::insert_into(dsl::email_verification_token)
+            .values((
+                dsl::token_hash.eq(hash_token(&verification_token)),
+                dsl::expires_at.eq(now + Self::token_lifetime()),
+                dsl::user_id.eq(user_id),
+            ))
+            .execute(conn)?;
+
+        Ok(verification_token)
+    }
+
+    fn send_mail(
+        existing_user: &User,
+        verification_token: &str,
+        mailer: &dyn MailTransport
+    ) -> Result<(), ServiceError> {
+        mailer.send(
+            &(Mail {
+                to: existing_user.email.clone(),
+                subject: "Verify your email address".to_string(),
+                body: format!(
+                    "Hi {},\n\nPlease confirm your email address by opening the following link:\n\n{}",
+                    existing_user.first_name,
+                    Self::verify_url(verification_token)
+                ),
+            })
+        )
+    }
+
+    /// Mails the link for a token created along with a new account. The account is already
+    /// committed by then, so failures are only logged and the user can ask for a new link.
+    pub fn send_after_signup(
+        existing_user: &User,
+        verification_token: &str,
+        mailer: &dyn MailTransport
+    ) {
+        if let Err(e) = Self::send_mail(existing_user, verification_token, mailer) {
+            error!("Error sending verification mail to user {}: {}", existing_user.id, e);
+        }
+    }
+
+    pub fn resend(
+        user_id: &i32,
+        mailer: &dyn MailTransport,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let existing_user = UserService::find(user_id, conn)?;
+
+        if existing_user.email_verified_at.is_some() {
+            return Err(ServiceError::BadRequest {
+                error_message: "Email address is already verified".to_string(),
+            });
+        }
+
+        // the token is only kept if the mail actually went out
+        conn.transaction(|conn| {
+            let verification_token = Self::create_token(&existing_user.id, conn)?;
+
+            Self::send_mail(&existing_user, &verification_token, mailer)
+        })
+    }
+
+    pub fn verify(token: &str, conn: &mut Connection) -> Result<(), ServiceError> {
+        use crate::schema::user;
+
+        conn.transaction(|conn| {
+            let verification_token = sql_query(
+                "SELECT user_id FROM public.email_verification_token
+                WHERE token_hash = $1
+                AND used_at IS NULL
+                AND expires_at > now()
+                FOR UPDATE"
+            )
+                .bind::<Text, _>(hash_token(token))
+                .get_result::<EmailVerificationToken>(conn)
+                .optional()?
+                .ok_or(ServiceError::BadRequest {
+                    error_message: "Invalid or expired verification token".to_string(),
+                })?;
+
+            sql_query(
+                "UPDATE public.email_verification_token SET used_at = now()
+                WHERE user_id = $1 AND used_at IS NULL"
+            )
+                .bind::<Integer, _>(verification_token.user_id)
+                .execute(conn)?;
+
+            diesel
+                ::update(user::dsl::user)
+                .filter(user::dsl::id.eq(verification_token.user_id))
+                .filter(user::dsl::email_verified_at.is_null())
+                .set((user::dsl::email_verified_at.eq(now), user::dsl::updated_at.eq(now)))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use diesel::prelude::*;
+use log::error;
+use crate::{
+    error::ServiceError,
+    models::image::{ Image, InsertableImage },
+    storage::FileStorage,
+    utils::{ token::generate_token, upload::ProcessedImage },
+};
+
+use super::Connection;
+
+pub struct ImageService;
+
+impl ImageService {
+    /// Saves the image and its thumbnail to the storage and records them for the store.
+    pub fn create(
+        processed: ProcessedImage,
+        store_id: &i32,
+        storage: &dyn FileStorage,
+        conn: &mut Connection
+    ) -> Result<Image, ServiceError> {
+        use crate::schema::image::dsl;
+
+        let base_key = format!("stores/{}/images/{}", store_id, &generate_token()[..24]);
+        let storage_key = format!("{}.{}", base_key, processed.extension);
+        let thumbnail_key = format!("{}-thumb.{}", base_key, processed.thumbnail_extension);
+
+        let url = storage.put(&storage_key, &processed.bytes, processed.content_type)?;
+
+        let thumbnail_url = match
+            storage.put(&thumbnail_key, &processed.thumbnail, processed.thumbnail_content_type)
+        {
+            Ok(thumbnail_url) => thumbnail_url,
+            Err(e) => {
+                Self::delete_keys(&[&storage_key], storage);
+                return Err(e);
+            }
+        };
+
+        let new_image = InsertableImage {
+            url,
+            thumbnail_url,
+            content_type: processed.content_type.to_string(),
+            size_bytes: processed.bytes.len() as i32,
+            width: processed.width as i32,
+            height: processed.height as i32,
+            store_id: store_id.to_owned(),
+            storage_key,
+            thumbnail_key,
+        };
+
+        let insert_result = diesel
+            ::insert_into(dsl::image)
+            .values(&new_image)
+            .returning(Image::as_returning())
+            .get_result(conn);
+
+        match insert_result {
+            Ok(image) => Ok(image),
+            Err(e) => {
+                Self::delete_keys(&[&new_image.storage_key, &new_image.thumbnail_key], storage);
+                Err(ServiceError::InternalServerError { error_message: e.to_string() })
+            }
+        }
+    }
+
+    pub fn find(id: &i32, conn: &mut Connection) -> Result<Image, ServiceError> {
+        use crate::schema::image::dsl;
+
+        dsl::image
+            .find(id)
+            .select(Image::as_select())
+            .first(conn)
+            .optional()?
+            .ok_or(ServiceError::NotFound { error_message: "Image not found".to_string() })
+    }
+
+    /// Deletes images that didn't end up used, rows and files.
+    pub fn delete_images(images: &[Image], storage: &dyn FileStorage, conn: &mut Connection) {
+        use crate::schema::image::dsl;
+
+        let image_ids: Vec<i32> = images
+            .iter()
+            .map(|image| image.id)
+            .collect();
+
+        if let Err(e) = diesel::delete(dsl::image.filter(dsl::id.eq_any(image_ids))).execute(conn) {
+            error!("Error deleting unused images: {}", e);
+        }
+
+        Self::delete_files(images, storage);
+    }
+
+    /// Removes the files of images whose rows are already gone. Failures are only logged,
+    /// the images are unreachable either way.
+    pub fn delete_files(images: &[Image], storage: &dyn FileStorage) {
+        for image in images {
+            Self::delete_keys(&[&image.storage_key, &image.thumbnail_key], storage);
+        }
+    }
+
+    fn delete_keys(keys: &[&str], storage: &dyn FileStorage) {
+        for key in keys {
+            if let Err(e) = storage.delete(key) {
+                error!("Error deleting stored file {}: {}", key, e);
+            }
+        }
+    }
+}
@@ -5,6 +5,18 @@ pub mod product;
 pub mod shopping_cart;
 pub mod order_item;
 pub mod product_item;
+pub mod order;
+pub mod payment_method;
+pub mod shipping;
+pub mod product_discount;
+pub mod stock_reservation;
+pub mod promo_code;
+pub mod product_search;
+pub mod session;
+pub mod password_reset;
+pub mod email_verification;
+pub mod image;
+pub mod product_import;
 
 use diesel::{ pg::PgConnection, r2d2::{ self, ConnectionManager } };
 
@@ -0,0 +1,410 @@
+use crate::{
+    models::order::{
+        InsertableOrder,
+        Order,
+        OrderStatus,
+        ShippingStatus,
+        OrderStatusHistory,
+        InsertableOrderStatusHistory,
+        OrderSummary,
+        OrderDetail,
+        OrderLine,
+        OrderLineRow,
+        OrderShipping,
+        StoreOrderSummary,
+    },
+    models::response::PaginatedResponse,
+    models::role::Permission,
+    controllers::order::{ MarkShippedBody, ListStoreOrdersQuery },
+    error::ServiceError,
+    utils::validation::validate,
+};
+use bigdecimal::{ BigDecimal, FromPrimitive };
+use diesel::{ prelude::*, sql_query, sql_types::Integer, Connection as _ };
+use super::{
+    Connection,
+    store::StoreService,
+    shipping::ShippingService,
+    order_item::OrderItemService,
+    product_item::ProductItemService,
+    product::ProductService,
+};
+use crate::schema::{
+    order,
+    order_item,
+    order_status_history,
+    payment_method,
+    product,
+    product_item,
+    shipping_information,
+    shipping_method,
+    user,
+};
+
+pub struct OrderService;
+
+impl OrderService {
+    pub fn create(new_order: InsertableOrder, conn: &mut Connection) -> Result<i32, ServiceError> {
+        let order_result = diesel
+            ::insert_into(order::dsl::order)
+            .values(new_order)
+            .returning(order::dsl::id)
+            .get_result::<i32>(conn);
+
+        match order_result {
+            Ok(id) => Ok(id),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn find_by_store_for_update(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Order, ServiceError> {
+        let order_result = sql_query(
+            "SELECT * FROM public.\"order\" WHERE id = $1 AND store_id = $2 FOR UPDATE"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<Order>(conn);
+
+        match order_result {
+            Ok(found_order) => Ok(found_order),
+            Err(_) => Err(ServiceError::NotFound { error_message: "Order not found".to_string() }),
+        }
+    }
+
+    pub fn record_status(
+        order_id: &i32,
+        from_status: Option<OrderStatus>,
+        to_status: OrderStatus,
+        changed_by_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let history_result = diesel
+            ::insert_into(order_status_history::dsl::order_status_history)
+            .values(InsertableOrderStatusHistory {
+                from_status: from_status.map(|status| status as i16),
+                to_status: to_status as i16,
+                order_id: order_id.to_owned(),
+                changed_by_id: changed_by_id.to_owned(),
+            })
+            .execute(conn);
+
+        match history_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn update_status(
+        order_id: &i32,
+        new_status: OrderStatus,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManageOrders, conn)?;
+
+        conn.transaction(|conn| {
+            Self::transition(order_id, new_status, store_id, user_id, conn).map(|_| ())
+        })
+    }
+
+    fn transition(
+        order_id: &i32,
+        new_status: OrderStatus,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Order, ServiceError> {
+        let found_order = Self::find_by_store_for_update(order_id, store_id, conn)?;
+
+        let current_status = OrderStatus::from_i16(found_order.status);
+
+        if !current_status.can_transition_to(&new_status) {
+            return Err(ServiceError::BadRequest {
+                error_message: format!(
+                    "Cannot move order {} from {} to {}",
+                    order_id,
+                    current_status,
+                    new_status
+                ),
+            });
+        }
+
+        diesel
+            ::update(order::dsl::order)
+            .filter(order::dsl::id.eq(order_id))
+            .set(order::dsl::status.eq(new_status as i16))
+            .execute(conn)?;
+
+        match new_status {
+            OrderStatus::Shipped =>
+                ShippingService::update_status(
+                    &found_order.shipping_information_id,
+                    ShippingStatus::Shipped,
+                    conn
+                )?,
+            OrderStatus::Delivered =>
+                ShippingService::update_status(
+                    &found_order.shipping_information_id,
+                    ShippingStatus::Delivered,
+                    conn
+                )?,
+            OrderStatus::Cancelled => {
+                for order_item in OrderItemService::find_by_order(order_id, conn)? {
+                    ProductItemService::increment_stock(
+                        &order_item.product_item_id,
+                        &order_item.quantity,
+                        conn
+                    )?;
+                }
+            }
+            _ => {}
+        }
+
+        Self::record_status(order_id, Some(current_status), new_status, user_id, conn)?;
+
+        Ok(found_order)
+    }
+
+    pub fn mark_shipped(
+        payload: MarkShippedBody,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        validate(&payload)?;
+
+        if payload.orders.is_empty() {
+            return Err(ServiceError::BadRequest {
+                error_message: "Missing orders to ship".to_string(),
+            });
+        }
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageOrders, conn)?;
+
+        conn.transaction(|conn| {
+            for shipment in payload.orders {
+                let shipped_order = Self::transition(
+                    &shipment.order_id,
+                    OrderStatus::Shipped,
+                    store_id,
+                    user_id,
+                    conn
+                )?;
+
+                ShippingService::set_tracking_number(
+                    &shipped_order.shipping_information_id,
+                    &shipment.tracking_number,
+                    conn
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub fn list_by_store(
+        query: ListStoreOrdersQuery,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<PaginatedResponse<StoreOrderSummary>, ServiceError> {
+        validate(&query)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ViewOrders, conn)?;
+
+        let page = query.page.unwrap_or(1);
+        let per_page = query.per_page.unwrap_or(20);
+
+        let build_query = || {
+            let mut filtered = order::table
+                .inner_join(user::table)
+                .inner_join(payment_method::table)
+                .inner_join(shipping_information::table)
+                .filter(order::store_id.eq(store_id))
+                .into_boxed();
+
+            if let Some(status) = query.status {
+                filtered = filtered.filter(order::status.eq(status as i16));
+            }
+            if let Some(from_date) = query.from_date {
+                filtered = filtered.filter(
+                    order::created_at.ge(from_date.and_hms_opt(0, 0, 0).unwrap())
+                );
+            }
+            if let Some(to_date) = query.to_date {
+                filtered = filtered.filter(
+                    order::created_at.le(to_date.and_hms_micro_opt(23, 59, 59, 999_999).unwrap())
+                );
+            }
+            if let Some(customer_email) = &query.customer_email {
+                filtered = filtered.filter(user::email.ilike(format!("%{}%", customer_email)));
+            }
+            if let Some(min_total) = query.min_total {
+                filtered = filtered.filter(
+                    order::total_price.ge(BigDecimal::from_f64(min_total).unwrap_or_default())
+                );
+            }
+            if let Some(max_total) = query.max_total {
+                filtered = filtered.filter(
+                    order::total_price.le(BigDecimal::from_f64(max_total).unwrap_or_default())
+                );
+            }
+
+            filtered
+        };
+
+        let total = build_query().count().get_result::<i64>(conn)?;
+
+        let orders = build_query()
+            .order(order::created_at.desc())
+            .limit(per_page)
+            .offset((page - 1) * per_page)
+            .select((
+                order::id,
+                order::status,
+                order::total_price,
+                order::total_discount,
+                order::created_at,
+                user::email,
+                payment_method::name,
+                shipping_information::tracking_number,
+            ))
+            .load::<StoreOrderSummary>(conn)?;
+
+        Ok(PaginatedResponse { items: orders, total, page, per_page })
+    }
+
+    pub fn list_status_history(
+        order_id: &i32,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<OrderStatusHistory>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ViewOrders, conn)?;
+
+        let history_result = sql_query(
+            "SELECT h.* FROM public.order_status_history h
+            INNER JOIN public.\"order\" o ON o.id = h.order_id
+            WHERE h.order_id = $1
+            AND o.store_id = $2
+            ORDER BY h.created_at ASC, h.id ASC"
+        )
+            .bind::<Integer, _>(order_id)
+            .bind::<Integer, _>(store_id)
+            .get_results::<OrderStatusHistory>(conn);
+
+        match history_result {
+            Ok(history) => Ok(history),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn list_by_customer(
+        customer_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<OrderSummary>, ServiceError> {
+        let orders_result = order::table
+            .inner_join(payment_method::table)
+            .filter(order::customer_id.eq(customer_id))
+            .order(order::created_at.desc())
+            .select((
+                order::id,
+                order::status,
+                order::total_price,
+                order::total_discount,
+                order::created_at,
+                order::store_id,
+                payment_method::name,
+                order::shipping_information_id,
+            ))
+            .load::<OrderSummary>(conn);
+
+        match orders_result {
+            Ok(orders) => Ok(orders),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn find_by_customer(
+        id: &i32,
+        customer_id: &i32,
+        conn: &mut Connection
+    ) -> Result<OrderDetail, ServiceError> {
+        let found_order = order::table
+            .inner_join(payment_method::table)
+            .filter(order::id.eq(id))
+            .filter(order::customer_id.eq(customer_id))
+            .select((
+                order::id,
+                order::status,
+                order::total_price,
+                order::total_discount,
+                order::created_at,
+                order::store_id,
+                payment_method::name,
+                order::shipping_information_id,
+            ))
+            .first::<OrderSummary>(conn)
+            .map_err(|_| ServiceError::NotFound { error_message: "Order not found".to_string() })?;
+
+        let shipping = shipping_information::table
+            .inner_join(shipping_method::table)
+            .filter(shipping_information::id.eq(found_order.shipping_information_id))
+            .select((
+                shipping_information::status,
+                shipping_information::tracking_number,
+                shipping_information::shipping_price,
+                shipping_method::name,
+                (
+                    shipping_information::address_id,
+                    shipping_information::address_line1,
+                    shipping_information::address_line2,
+                    shipping_information::number,
+                    shipping_information::city,
+                    shipping_information::country,
+                    shipping_information::postal_code,
+                    shipping_information::phone_country_code,
+                    shipping_information::phone_number,
+                ),
+            ))
+            .first::<OrderShipping>(conn)?;
+
+        let rows = order_item::table
+            .inner_join(product_item::table.inner_join(product::table))
+            .filter(order_item::order_id.eq(id))
+            .order(order_item::id.asc())
+            .select((
+                order_item::id,
+                order_item::quantity,
+                order_item::unit_price,
+                product_item::id,
+                product::id,
+                product::name,
+                product_item::sku,
+                product_item::image_url,
+            ))
+            .load::<OrderLineRow>(conn)?;
+
+        let item_ids: Vec<i32> = rows
+            .iter()
+            .map(|row| row.product_item_id)
+            .collect();
+
+        let mut variants_by_item = ProductService::item_variants(&item_ids, conn)?;
+
+        let items = rows
+            .into_iter()
+            .map(|line| OrderLine {
+                variants: variants_by_item.remove(&line.product_item_id).unwrap_or_default(),
+                line,
+            })
+            .collect();
+
+        Ok(OrderDetail { order: found_order, shipping, items })
+    }
+}
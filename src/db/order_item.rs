@@ -1,9 +1,14 @@
+use bigdecimal::BigDecimal;
 use crate::{
-    models::{ order::{ InsertableOrderItem, OrderItem }, response::IDResponse },
+    models::{
+        order::{ InsertableOrderItem, OrderItem },
+        product::ProductItem,
+        response::IDResponse,
+    },
     error::ServiceError,
 };
-use diesel::{ prelude::*, sql_query, sql_types::Integer };
-use super::Connection;
+use diesel::{ prelude::*, sql_query, sql_types::{ Integer, Numeric } };
+use super::{ Connection, product_discount::ProductDiscountService };
 use crate::schema::order_item;
 
 pub struct OrderItemService;
@@ -14,7 +19,7 @@ impl OrderItemService {
         conn: &mut Connection
     ) -> Result<Vec<OrderItem>, ServiceError> {
         let order_items_result = sql_query(
-            "SELECT id FROM public.order_item WHERE shopping_cart_id = $1"
+            "SELECT * FROM public.order_item WHERE shopping_cart_id = $1"
         )
             .bind::<Integer, _>(shoping_cart_id)
             .get_results::<OrderItem>(conn);
@@ -26,10 +31,34 @@ impl OrderItemService {
         }
     }
 
+    pub fn find_by_order(
+        order_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<OrderItem>, ServiceError> {
+        let order_items_result = sql_query("SELECT * FROM public.order_item WHERE order_id = $1")
+            .bind::<Integer, _>(order_id)
+            .get_results::<OrderItem>(conn);
+
+        match order_items_result {
+            Ok(items) => Ok(items),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
     pub fn create(
-        new_order_item: InsertableOrderItem,
+        product_item: &ProductItem,
+        quantity: &i32,
+        shopping_cart_id: &i32,
         conn: &mut Connection
     ) -> Result<IDResponse<i32>, ServiceError> {
+        let new_order_item = InsertableOrderItem {
+            order_id: None,
+            product_item_id: product_item.id,
+            quantity: quantity.to_owned(),
+            shopping_cart_id: Some(shopping_cart_id.to_owned()),
+            unit_price: ProductDiscountService::effective_price(product_item, conn)?,
+        };
+
         let order_item_result = diesel
             ::insert_into(order_item::dsl::order_item)
             .values(new_order_item)
@@ -56,6 +85,38 @@ impl OrderItemService {
             Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
         }
     }
+    pub fn update_unit_price(
+        unit_price: &BigDecimal,
+        id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query("UPDATE public.order_item SET unit_price = $1 WHERE id = $2")
+            .bind::<Numeric, _>(unit_price)
+            .bind::<Integer, _>(id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+    pub fn move_to_order(
+        shopping_cart_id: &i32,
+        order_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query(
+            "UPDATE public.order_item SET order_id = $1, shopping_cart_id = NULL WHERE shopping_cart_id = $2"
+        )
+            .bind::<Integer, _>(order_id)
+            .bind::<Integer, _>(shopping_cart_id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
     pub fn delete(ids: Vec<i32>, conn: &mut Connection) -> Result<(), ServiceError> {
         let delete_result = diesel
             ::delete(order_item::dsl::order_item)
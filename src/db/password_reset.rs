@@ -0,0 +1,136 @@
+use diesel::{
+    prelude::*,
+    dsl::{ now, IntervalDsl },
+    pg::data_types::PgInterval,
+    sql_query,
+    sql_types::{ Integer, Text },
+    Connection as _,
+};
+use log::error;
+use crate::{
+    controllers::auth::{ PasswordResetRequestPayload, PasswordResetConfirmPayload },
+    error::ServiceError,
+    mail::{ Mail, MailTransport },
+    models::{ password_reset::PasswordResetToken, user::User },
+    utils::{ password_hash::PasswordHash, token::{ generate_token, hash_token }, validation::validate },
+};
+use super::{ Connection, session::SessionService };
+
+const DEFAULT_PASSWORD_RESET_MINUTES: i32 = 60;
+
+pub struct PasswordResetService;
+
+impl PasswordResetService {
+    fn token_lifetime() -> PgInterval {
+        std::env::var("PASSWORD_RESET_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse().ok())
+            .unwrap_or(DEFAULT_PASSWORD_RESET_MINUTES)
+            .minutes()
+    }
+
+    /// Mails a reset token to the user. Unknown emails are silently accepted so the
+    /// endpoint can't be used to find out who has an account.
+    pub fn request(
+        payload: PasswordResetRequestPayload,
+        mailer: &dyn MailTransport,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::{ user, password_reset_token::dsl };
+
+        validate(&payload)?;
+
+        let existing_user = user::dsl::user
+            .filter(user::dsl::email.eq(&payload.email))
+            .get_result::<User>(conn)
+            .optional()?;
+
+        let existing_user = match existing_user {
+            Some(existing_user) => existing_user,
+            None => {
+                return Ok(());
+            }
+        };
+
+        let reset_token = generate_token();
+
+        // the token is only kept if the mail actually went out
+        let request_result = conn.transaction(|conn| {
+            diesel
+                ::insert_into(dsl::password_reset_token)
+                .values((
+                    dsl::token_hash.eq(hash_token(&reset_token)),
+                    dsl::expires_at.eq(now + Self::token_lifetime()),
+                    dsl::user_id.eq(existing_user.id),
+                ))
+                .execute(conn)?;
+
+            mailer.send(
+                &(Mail {
+                    to: existing_user.email.clone(),
+                    subject: "Reset your password".to_string(),
+                    body: format!(
+                        "Hi {},\n\nUse the following token to choose a new password:\n\n{}\n\nIf you didn't ask for a password reset you can ignore this email.",
+                        existing_user.first_name,
+                        reset_token
+                    ),
+                })
+            )
+        });
+
+        // answering with an error here would reveal that the account exists
+        if let Err(e) = request_result {
+            error!("Error sending password reset mail: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the new password and burns every pending reset token of the user. Open
+    /// sessions are revoked as well, since whoever had the old password may own them.
+    pub fn confirm(
+        payload: PasswordResetConfirmPayload,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::user::dsl::*;
+
+        validate(&payload)?;
+
+        conn.transaction(|conn| {
+            let reset_token = sql_query(
+                "SELECT user_id FROM public.password_reset_token
+                WHERE token_hash = $1
+                AND used_at IS NULL
+                AND expires_at > now()
+                FOR UPDATE"
+            )
+                .bind::<Text, _>(hash_token(&payload.token))
+                .get_result::<PasswordResetToken>(conn)
+                .optional()?
+                .ok_or(ServiceError::BadRequest {
+                    error_message: "Invalid or expired password reset token".to_string(),
+                })?;
+
+            sql_query(
+                "UPDATE public.password_reset_token SET used_at = now()
+                WHERE user_id = $1 AND used_at IS NULL"
+            )
+                .bind::<Integer, _>(reset_token.user_id)
+                .execute(conn)?;
+
+            let hashed_password = PasswordHash::create_hash(&payload.password);
+
+            diesel
+                ::update(user)
+                .filter(id.eq(reset_token.user_id))
+                .set((
+                    password.eq(hashed_password.password_hash),
+                    salt.eq(hashed_password.salt),
+                    updated_at.eq(now),
+                ))
+                .execute(conn)?;
+
+            SessionService::revoke_all(&reset_token.user_id, conn)
+        })
+    }
+}
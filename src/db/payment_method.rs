@@ -0,0 +1,187 @@
+use crate::{
+    controllers::payment_method::PaymentMethodBody,
+    models::{
+        payment_method::{ PaymentMethod, InsertablePaymentMethod },
+        response::IDResponse,
+        role::Permission,
+    },
+    error::ServiceError,
+    utils::validation::validate,
+};
+use super::{ Connection, store::StoreService };
+use diesel::sql_types::Integer;
+use diesel::{ prelude::*, sql_query };
+
+pub struct PaymentMethodService;
+
+impl PaymentMethodService {
+    pub fn find_active(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<PaymentMethod, ServiceError> {
+        let payment_method_result = sql_query(
+            "SELECT * FROM public.payment_method 
+            WHERE id = $1 
+            AND store_id = $2 
+            AND inactive = false 
+            AND deleted = false"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<PaymentMethod>(conn);
+
+        match payment_method_result {
+            Ok(payment_method) => Ok(payment_method),
+            Err(_) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Payment method not found".to_string(),
+                }),
+        }
+    }
+
+    pub fn find(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<PaymentMethod, ServiceError> {
+        let payment_method_result = sql_query(
+            "SELECT * FROM public.payment_method WHERE id = $1 AND store_id = $2 AND deleted = false"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<PaymentMethod>(conn);
+
+        match payment_method_result {
+            Ok(payment_method) => Ok(payment_method),
+            Err(_) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Payment method not found".to_string(),
+                }),
+        }
+    }
+
+    /// What customers can pick from at checkout.
+    pub fn list_active(
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<PaymentMethod>, ServiceError> {
+        let payment_methods_result = sql_query(
+            "SELECT * FROM public.payment_method
+            WHERE store_id = $1 AND inactive = false AND deleted = false
+            ORDER BY name"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<PaymentMethod>(conn);
+
+        match payment_methods_result {
+            Ok(payment_methods) => Ok(payment_methods),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn list(
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<PaymentMethod>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let payment_methods_result = sql_query(
+            "SELECT * FROM public.payment_method WHERE store_id = $1 AND deleted = false ORDER BY name"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<PaymentMethod>(conn);
+
+        match payment_methods_result {
+            Ok(payment_methods) => Ok(payment_methods),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn create(
+        payload: PaymentMethodBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        use crate::schema::payment_method;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        match
+            diesel
+                ::insert_into(payment_method::dsl::payment_method)
+                .values((
+                    InsertablePaymentMethod {
+                        name: payload.name,
+                        store_id: store_id.to_owned(),
+                    },
+                    payment_method::dsl::inactive.eq(payload.inactive),
+                ))
+                .returning(payment_method::dsl::id)
+                .get_result::<i32>(conn)
+        {
+            Ok(id) => Ok(IDResponse { id }),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn edit(
+        id: &i32,
+        payload: PaymentMethodBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::payment_method::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let existing_payment_method = Self::find(id, store_id, conn)?;
+
+        let update_result = diesel
+            ::update(dsl::payment_method)
+            .filter(dsl::id.eq(existing_payment_method.id))
+            .set((
+                dsl::name.eq(payload.name),
+                dsl::inactive.eq(payload.inactive),
+                dsl::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    /// Soft deletes, orders placed with the method keep pointing at it.
+    pub fn delete(
+        id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::payment_method::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let existing_payment_method = Self::find(id, store_id, conn)?;
+
+        let delete_result = diesel
+            ::update(dsl::payment_method)
+            .filter(dsl::id.eq(existing_payment_method.id))
+            .set((dsl::deleted.eq(true), dsl::updated_at.eq(diesel::dsl::now)))
+            .execute(conn);
+
+        match delete_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
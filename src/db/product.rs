@@ -1,21 +1,61 @@
+use std::collections::HashMap;
+
 use bigdecimal::{ BigDecimal, FromPrimitive };
-use diesel::{ prelude::*, sql_query, sql_types::Integer };
+use diesel::{
+    prelude::*,
+    result::{ DatabaseErrorKind, Error as DieselError },
+    sql_query,
+    sql_types::{ Array, Bool, Integer },
+    Connection as _,
+};
 use crate::{
-    controllers::product::{ CreateCategoryBody, CreateVariantBody, CreateProductBody },
+    controllers::product::{
+        CreateCategoryBody,
+        EditCategoryBody,
+        MoveCategoryBody,
+        ReassignQuery,
+        CreateVariantBody,
+        CreateOptionBody,
+        EditOptionBody,
+        OptionValueBody,
+        CreateProductBody,
+        EditProductBody,
+        CatalogQuery,
+        REGEX_OPTION_TEXT,
+    },
     models::{
         product::{
+            Product,
             InsertableCategory,
             InsertableVariant,
             InsertableProduct,
             InsertableProductItem,
+            InsertableProductOption,
+            InsertableItemVariant,
+            ItemVariantRow,
+            ItemCombination,
+            BlockingProduct,
+            ProductOption,
+            ProductOptionDetail,
+            ProductOptionValue,
             ProductVariant,
             ProductCategory,
+            CategoryNode,
+            CategoryId,
+            CatalogProduct,
+            CatalogProductDetail,
+            CatalogItem,
+            CatalogItemRow,
+            CatalogVariant,
+            ReservedStock,
         },
-        response::IDResponse,
+        image::ItemImage,
+        response::{ IDResponse, PaginatedResponse },
+        role::Permission,
     },
     error::ServiceError,
-    utils::validation::validate,
-    db::store::StoreService,
+    utils::{ validation::validate, slug::{ slugify, numbered_slug } },
+    db::{ store::StoreService, product_discount::ProductDiscountService },
 };
 
 use super::Connection;
@@ -23,6 +63,28 @@ use super::Connection;
 pub struct ProductService;
 
 impl ProductService {
+    /// Slug for a new category of the store, numbered when another category took it.
+    fn available_category_slug(
+        name: &str,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<String, ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        let base_slug = match slugify(name) {
+            slug if slug.is_empty() => "category".to_string(),
+            slug => slug,
+        };
+
+        let taken = dsl::product_category
+            .filter(dsl::store_id.eq(store_id))
+            .filter(dsl::slug.eq(&base_slug).or(dsl::slug.like(format!("{}-%", base_slug))))
+            .select(dsl::slug)
+            .load::<String>(conn)?;
+
+        Ok(numbered_slug(&base_slug, &taken))
+    }
+
     pub fn create_category(
         payload: CreateCategoryBody,
         store_id: &i32,
@@ -33,11 +95,22 @@ impl ProductService {
 
         validate(&payload)?;
 
-        StoreService::check_store_admin(store_id, user_id, conn)?;
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        if let Some(parent_id) = payload.parent_id {
+            Self::check_category(&parent_id, store_id, conn)?;
+        }
+
+        let slug = match payload.slug {
+            Some(slug) => slug,
+            None => Self::available_category_slug(&payload.name, store_id, conn)?,
+        };
 
         let new_category = InsertableCategory {
             name: payload.name,
             store_id: store_id.to_owned(),
+            slug,
+            parent_id: payload.parent_id,
         };
 
         match
@@ -48,40 +121,595 @@ impl ProductService {
                 .get_result::<i32>(conn)
         {
             Ok(result_id) => Ok(IDResponse { id: result_id }),
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+            Err(e) => Err(Self::map_write_error(e, "Category slug is already taken")),
         }
     }
 
+    pub fn find_category(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ProductCategory, ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        dsl::product_category
+            .filter(dsl::id.eq(id))
+            .filter(dsl::store_id.eq(store_id))
+            .first::<ProductCategory>(conn)
+            .optional()?
+            .ok_or(ServiceError::NotFound { error_message: "Category not found".to_string() })
+    }
+
+    /// Ids of the categories and all of their subcategories, at any depth.
+    pub fn category_subtree_ids(
+        category_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<Vec<i32>, ServiceError> {
+        let subtree = sql_query(
+            "WITH RECURSIVE subtree AS (
+                SELECT id FROM public.product_category WHERE id = ANY($1)
+                UNION
+                SELECT c.id FROM public.product_category c
+                INNER JOIN subtree s ON c.parent_id = s.id
+            )
+            SELECT id FROM subtree"
+        )
+            .bind::<Array<Integer>, _>(category_ids)
+            .get_results::<CategoryId>(conn)?;
+
+        Ok(
+            subtree
+                .into_iter()
+                .map(|category| category.id)
+                .collect()
+        )
+    }
+
+    /// Moves the category, together with its subcategories, under `parent_id`.
+    pub fn move_category(
+        id: &i32,
+        payload: MoveCategoryBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        conn.transaction(|conn| {
+            // concurrent moves within a store could otherwise create a cycle between them
+            dsl::product_category
+                .filter(dsl::store_id.eq(store_id))
+                .select(dsl::id)
+                .for_update()
+                .load::<i32>(conn)?;
+
+            let category = Self::find_category(id, store_id, conn)?;
+
+            if let Some(parent_id) = payload.parent_id {
+                Self::find_category(&parent_id, store_id, conn)?;
+
+                if Self::category_subtree_ids(&[category.id], conn)?.contains(&parent_id) {
+                    return Err(ServiceError::BadRequest {
+                        error_message: "A category can't be moved under itself or one of its subcategories".to_string(),
+                    });
+                }
+            }
+
+            diesel
+                ::update(dsl::product_category)
+                .filter(dsl::id.eq(category.id))
+                .set(dsl::parent_id.eq(payload.parent_id))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn edit_category(
+        id: &i32,
+        payload: EditCategoryBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let category = Self::find_category(id, store_id, conn)?;
+
+        diesel
+            ::update(dsl::product_category)
+            .filter(dsl::id.eq(category.id))
+            .set((
+                dsl::name.eq(payload.name),
+                dsl::slug.eq(payload.slug.unwrap_or(category.slug)),
+            ))
+            .execute(conn)
+            .map_err(|e| Self::map_write_error(e, "Category slug is already taken"))?;
+
+        Ok(())
+    }
+
+    /// Deletes the category, its subcategories are moved up to its parent. Products of
+    /// the category block the delete unless `reassign_to` names a category to move them to.
+    pub fn delete_category(
+        id: &i32,
+        query: ReassignQuery,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::{ product, product_category::dsl };
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        conn.transaction(|conn| {
+            // also holds back products being created in the category meanwhile
+            dsl::product_category
+                .filter(dsl::store_id.eq(store_id))
+                .select(dsl::id)
+                .for_update()
+                .load::<i32>(conn)?;
+
+            let category = Self::find_category(id, store_id, conn)?;
+
+            let blocking_products = sql_query(
+                "SELECT id, name, deleted FROM public.product WHERE category_id = $1 ORDER BY id"
+            )
+                .bind::<Integer, _>(category.id)
+                .get_results::<BlockingProduct>(conn)?;
+
+            match query.reassign_to {
+                Some(target_id) => {
+                    if target_id == category.id {
+                        return Err(ServiceError::BadRequest {
+                            error_message: "A category can't be reassigned to itself".to_string(),
+                        });
+                    }
+
+                    Self::find_category(&target_id, store_id, conn)?;
+
+                    diesel
+                        ::update(product::dsl::product)
+                        .filter(product::dsl::category_id.eq(category.id))
+                        .set(product::dsl::category_id.eq(target_id))
+                        .execute(conn)?;
+                }
+                None if !blocking_products.is_empty() => {
+                    return Err(ServiceError::Conflict {
+                        error_message: format!(
+                            "{} is used by {} product(s), reassign them to another category first",
+                            category.name,
+                            blocking_products.len()
+                        ),
+                        blocking_products,
+                    });
+                }
+                None => {}
+            }
+
+            diesel
+                ::update(dsl::product_category)
+                .filter(dsl::parent_id.eq(category.id))
+                .set(dsl::parent_id.eq(category.parent_id))
+                .execute(conn)?;
+
+            diesel::delete(dsl::product_category).filter(dsl::id.eq(category.id)).execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    /// Every category of the store, nested under its parent and sorted by name.
+    pub fn category_tree(
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<CategoryNode>, ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        let categories = dsl::product_category
+            .filter(dsl::store_id.eq(store_id))
+            .order(dsl::name.asc())
+            .load::<ProductCategory>(conn)?;
+
+        let mut children_by_parent: HashMap<Option<i32>, Vec<ProductCategory>> = HashMap::new();
+
+        for category in categories {
+            children_by_parent.entry(category.parent_id).or_default().push(category);
+        }
+
+        Ok(Self::nest_categories(None, &mut children_by_parent))
+    }
+
+    fn nest_categories(
+        parent_id: Option<i32>,
+        children_by_parent: &mut HashMap<Option<i32>, Vec<ProductCategory>>
+    ) -> Vec<CategoryNode> {
+        children_by_parent
+            .remove(&parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|category| CategoryNode {
+                children: Self::nest_categories(Some(category.id), children_by_parent),
+                category,
+            })
+            .collect()
+    }
+
+    fn map_write_error(e: DieselError, message: &str) -> ServiceError {
+        match e {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) =>
+                ServiceError::BadRequest { error_message: message.to_string() },
+            _ => ServiceError::InternalServerError { error_message: e.to_string() },
+        }
+    }
+
+    pub fn find_option(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ProductOption, ServiceError> {
+        let option_result = sql_query(
+            "SELECT * FROM public.product_option WHERE id = $1 AND store_id = $2"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<ProductOption>(conn);
+
+        match option_result {
+            Ok(option) => Ok(option),
+            Err(_) => Err(ServiceError::NotFound { error_message: "Option not found".to_string() }),
+        }
+    }
+
+    fn insert_option_values(
+        option: &ProductOption,
+        values: Vec<String>,
+        conn: &mut Connection
+    ) -> Result<Vec<i32>, ServiceError> {
+        use crate::schema::product_variant;
+
+        let mut new_values = Vec::with_capacity(values.len());
+
+        for value in values {
+            let value = value.trim().to_string();
+
+            if value.is_empty() || value.chars().count() > 60 {
+                return Err(ServiceError::BadRequest {
+                    error_message: "Option values must have between 1 and 60 characters".to_string(),
+                });
+            }
+
+            if !REGEX_OPTION_TEXT.is_match(&value) {
+                return Err(ServiceError::BadRequest {
+                    error_message: "Option values can't contain ';' or ':'".to_string(),
+                });
+            }
+
+            new_values.push(InsertableVariant {
+                value,
+                store_id: option.store_id,
+                option_id: option.id,
+            });
+        }
+
+        diesel
+            ::insert_into(product_variant::dsl::product_variant)
+            .values(new_values)
+            .returning(product_variant::dsl::id)
+            .get_results::<i32>(conn)
+            .map_err(|e| Self::map_write_error(e, &format!("{} already has that value", option.name)))
+    }
+
+    pub fn create_option(
+        payload: CreateOptionBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        use crate::schema::product_option;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        conn.transaction(|conn| {
+            let option = diesel
+                ::insert_into(product_option::dsl::product_option)
+                .values(InsertableProductOption {
+                    name: payload.name.trim().to_string(),
+                    store_id: store_id.to_owned(),
+                })
+                .get_result::<ProductOption>(conn)
+                .map_err(|e| Self::map_write_error(e, "Option already exists"))?;
+
+            Self::insert_option_values(&option, payload.values, conn)?;
+
+            Ok(IDResponse { id: option.id })
+        })
+    }
+
+    pub fn create_option_value(
+        option_id: &i32,
+        payload: OptionValueBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let option = Self::find_option(option_id, store_id, conn)?;
+
+        let ids = Self::insert_option_values(&option, vec![payload.value], conn)?;
+
+        Ok(IDResponse { id: ids[0] })
+    }
+
     pub fn create_variant(
         payload: CreateVariantBody,
         user_id: &i32,
         store_id: &i32,
         conn: &mut Connection
     ) -> Result<IDResponse<i32>, ServiceError> {
-        use crate::schema::product_variant;
+        use crate::schema::product_option::dsl;
 
         validate(&payload)?;
 
-        StoreService::check_store_admin(store_id, user_id, conn)?;
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
 
-        let new_variant = InsertableVariant {
-            name: payload.name,
-            value: payload.value,
-            store_id: store_id.to_owned(),
-        };
+        conn.transaction(|conn| {
+            let name = payload.name.trim().to_string();
+
+            let existing_option = dsl::product_option
+                .filter(dsl::name.eq(&name))
+                .filter(dsl::store_id.eq(store_id))
+                .first::<ProductOption>(conn)
+                .optional()?;
+
+            let option = match existing_option {
+                Some(option) => option,
+                None =>
+                    diesel
+                        ::insert_into(dsl::product_option)
+                        .values(InsertableProductOption { name, store_id: store_id.to_owned() })
+                        .get_result::<ProductOption>(conn)?,
+            };
+
+            let ids = Self::insert_option_values(&option, vec![payload.value], conn)?;
+
+            Ok(IDResponse { id: ids[0] })
+        })
+    }
+
+    pub fn edit_option(
+        id: &i32,
+        payload: EditOptionBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_option::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let option = Self::find_option(id, store_id, conn)?;
+
+        diesel
+            ::update(dsl::product_option)
+            .filter(dsl::id.eq(option.id))
+            .set(dsl::name.eq(payload.name.trim()))
+            .execute(conn)
+            .map_err(|e| Self::map_write_error(e, "Option already exists"))?;
+
+        Ok(())
+    }
+
+    /// Products with items that use any of `variant_ids`, deleted items included.
+    fn variant_blocking_products(
+        variant_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<Vec<BlockingProduct>, ServiceError> {
+        let blocking_products = sql_query(
+            "SELECT DISTINCT p.id, p.name, p.deleted
+            FROM public.product p
+            INNER JOIN public.product_item i ON i.product_id = p.id
+            INNER JOIN public.product_item_variant iv ON iv.product_item_id = i.id
+            WHERE iv.variant_id = ANY($1)
+            ORDER BY p.id"
+        )
+            .bind::<Array<Integer>, _>(variant_ids)
+            .get_results::<BlockingProduct>(conn)?;
+
+        Ok(blocking_products)
+    }
+
+    /// Deletes the option type with all of its values, refused while items use any of them.
+    pub fn delete_option(
+        id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::{ product_option, product_variant::dsl };
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        conn.transaction(|conn| {
+            let option = Self::find_option(id, store_id, conn)?;
+
+            let variant_ids = dsl::product_variant
+                .filter(dsl::option_id.eq(option.id))
+                .select(dsl::id)
+                .for_update()
+                .load::<i32>(conn)?;
+
+            let blocking_products = Self::variant_blocking_products(&variant_ids, conn)?;
+
+            if !blocking_products.is_empty() {
+                return Err(ServiceError::Conflict {
+                    error_message: format!(
+                        "{} values are used by {} product(s)",
+                        option.name,
+                        blocking_products.len()
+                    ),
+                    blocking_products,
+                });
+            }
 
-        match
             diesel
-                ::insert_into(product_variant::dsl::product_variant)
-                .values(new_variant)
-                .returning(product_variant::dsl::id)
-                .get_result(conn)
-        {
-            Ok(id) => Ok(IDResponse { id }),
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+                ::delete(product_option::dsl::product_option)
+                .filter(product_option::dsl::id.eq(option.id))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn find_variant(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ProductVariant, ServiceError> {
+        let variant_result = sql_query(
+            "SELECT v.id, v.option_id, o.name, v.value
+            FROM public.product_variant v
+            INNER JOIN public.product_option o ON o.id = v.option_id
+            WHERE v.id = $1 AND v.store_id = $2"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<ProductVariant>(conn);
+
+        match variant_result {
+            Ok(variant) => Ok(variant),
+            Err(_) => Err(ServiceError::NotFound { error_message: "Variant not found".to_string() }),
         }
     }
 
+    pub fn edit_variant(
+        id: &i32,
+        payload: OptionValueBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_variant::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let variant = Self::find_variant(id, store_id, conn)?;
+
+        diesel
+            ::update(dsl::product_variant)
+            .filter(dsl::id.eq(variant.id))
+            .set(dsl::value.eq(payload.value.trim()))
+            .execute(conn)
+            .map_err(|e| Self::map_write_error(e, &format!("{} already has that value", variant.name)))?;
+
+        Ok(())
+    }
+
+    /// Deletes an option value. Items using it block the delete unless `reassign_to` names
+    /// another value of the same option type to give them instead.
+    pub fn delete_variant(
+        id: &i32,
+        query: ReassignQuery,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::{ product_item_variant, product_variant::dsl };
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        conn.transaction(|conn| {
+            let variant = Self::find_variant(id, store_id, conn)?;
+
+            // holds back items being given the value meanwhile
+            dsl::product_variant.filter(dsl::id.eq(variant.id)).for_update().execute(conn)?;
+
+            let blocking_products = Self::variant_blocking_products(&[variant.id], conn)?;
+
+            match query.reassign_to {
+                Some(target_id) => {
+                    let target = Self::find_variant(&target_id, store_id, conn)?;
+
+                    if target.id == variant.id || target.option_id != variant.option_id {
+                        return Err(ServiceError::BadRequest {
+                            error_message: format!(
+                                "Items can only be reassigned to another {} value",
+                                variant.name
+                            ),
+                        });
+                    }
+
+                    for product in &blocking_products {
+                        let mut keys: Vec<Vec<i32>> = Self::item_combinations(&product.id, conn)?
+                            .into_iter()
+                            .filter(|combination| !combination.variant_ids.is_empty())
+                            .map(|combination| {
+                                let mut key: Vec<i32> = combination.variant_ids
+                                    .into_iter()
+                                    .map(|variant_id| if variant_id == variant.id { target.id } else { variant_id })
+                                    .collect();
+                                key.sort_unstable();
+                                key
+                            })
+                            .collect();
+
+                        let item_count = keys.len();
+                        keys.sort();
+                        keys.dedup();
+
+                        if keys.len() != item_count {
+                            return Err(ServiceError::BadRequest {
+                                error_message: format!(
+                                    "{} would have two items with {}: {}",
+                                    product.name,
+                                    target.name,
+                                    target.value
+                                ),
+                            });
+                        }
+                    }
+
+                    diesel
+                        ::update(product_item_variant::dsl::product_item_variant)
+                        .filter(product_item_variant::dsl::variant_id.eq(variant.id))
+                        .set(product_item_variant::dsl::variant_id.eq(target.id))
+                        .execute(conn)?;
+                }
+                None if !blocking_products.is_empty() => {
+                    return Err(ServiceError::Conflict {
+                        error_message: format!(
+                            "{}: {} is used by {} product(s), reassign their items to another value first",
+                            variant.name,
+                            variant.value,
+                            blocking_products.len()
+                        ),
+                        blocking_products,
+                    });
+                }
+                None => {}
+            }
+
+            diesel::delete(dsl::product_variant).filter(dsl::id.eq(variant.id)).execute(conn)?;
+
+            Ok(())
+        })
+    }
+
     pub fn create(
         payload: CreateProductBody,
         user_id: &i32,
@@ -97,61 +725,379 @@ impl ProductService {
             });
         }
 
-        StoreService::check_store_admin(store_id, user_id, conn)?;
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
 
-        let new_product = InsertableProduct {
-            name: payload.name,
-            category_id: payload.category_id,
-            store_id: store_id.to_owned(),
-        };
+        Self::check_category(&payload.category_id, store_id, conn)?;
 
-        match
-            diesel
+        let mut item_variants = Vec::with_capacity(payload.data.len());
+
+        for p_data in &payload.data {
+            let variants = Self::find_variants(&p_data.variant_ids, store_id, conn)?;
+
+            Self::check_one_value_per_option(&variants)?;
+
+            item_variants.push(variants);
+        }
+
+        let mut taken_keys: Vec<Vec<i32>> = Vec::new();
+
+        for variants in &item_variants {
+            Self::check_combination(&taken_keys, variants)?;
+
+            taken_keys.push(Self::combination_key(variants));
+        }
+
+        conn.transaction(|conn| {
+            let new_product = InsertableProduct {
+                name: payload.name,
+                category_id: payload.category_id,
+                store_id: store_id.to_owned(),
+            };
+
+            let product_id = diesel
                 ::insert_into(product::dsl::product)
                 .values(new_product)
                 .returning(product::dsl::id)
-                .get_result::<i32>(conn)
-        {
-            Ok(product_id) => {
-                let product_items: Vec<InsertableProductItem> = payload.data
-                    .into_iter()
-                    .map(|p_data| InsertableProductItem {
-                        description: Some(p_data.description),
-                        image_url: Some(p_data.image),
-                        sku: Some(p_data.sku),
-                        price: BigDecimal::from_f32(p_data.price).unwrap(),
-                        stock: p_data.stock,
-                        variant_id: p_data.variant_id,
-                        store_id: store_id.to_owned(),
-                        product_id: product_id,
-                    })
-                    .collect();
-
-                let product_items_result = diesel
-                    ::insert_into(product_item::dsl::product_item)
-                    .values(product_items)
-                    .execute(conn);
-
-                if product_items_result.is_err() {
-                    return Err(ServiceError::InternalServerError {
-                        error_message: product_items_result.unwrap_err().to_string(),
-                    });
-                }
+                .get_result::<i32>(conn)?;
+
+            let product_items: Vec<InsertableProductItem> = payload.data
+                .into_iter()
+                .map(|p_data| InsertableProductItem {
+                    description: Some(p_data.description),
+                    image_url: Some(p_data.image),
+                    sku: Some(p_data.sku),
+                    price: BigDecimal::from_f32(p_data.price).unwrap(),
+                    stock: p_data.stock,
+                    weight_grams: p_data.weight_grams,
+                    store_id: store_id.to_owned(),
+                    product_id,
+                })
+                .collect();
 
-                Ok(IDResponse { id: product_id })
+            let item_ids = diesel
+                ::insert_into(product_item::dsl::product_item)
+                .values(product_items)
+                .returning(product_item::dsl::id)
+                .get_results::<i32>(conn)?;
+
+            for (item_id, variants) in item_ids.iter().zip(item_variants.iter()) {
+                Self::set_item_variants(item_id, variants, conn)?;
             }
-            Err(e) =>
-                Err(ServiceError::InternalServerError {
-                    error_message: e.to_string(),
-                }),
+
+            Ok(IDResponse { id: product_id })
+        })
+    }
+
+    pub fn find_by_store(
+        id: &i32,
+        store_id: &i32,
+        deleted: bool,
+        conn: &mut Connection
+    ) -> Result<Product, ServiceError> {
+        let product_result = sql_query(
+            "SELECT * FROM public.product WHERE id = $1 AND store_id = $2 AND deleted = $3"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .bind::<Bool, _>(deleted)
+            .get_result::<Product>(conn);
+
+        match product_result {
+            Ok(found_product) => Ok(found_product),
+            Err(_) => Err(ServiceError::NotFound { error_message: "Product not found".to_string() }),
+        }
+    }
+
+    pub fn check_category(
+        category_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        let category_count = dsl::product_category
+            .filter(dsl::id.eq(category_id))
+            .filter(dsl::store_id.eq(store_id))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        if category_count == 0 {
+            return Err(ServiceError::BadRequest {
+                error_message: "Category not found".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Option values of the store with the given ids, ordered by option type.
+    pub fn find_variants(
+        variant_ids: &[i32],
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ProductVariant>, ServiceError> {
+        if variant_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let variants = sql_query(
+            "SELECT v.id, v.option_id, o.name, v.value
+            FROM public.product_variant v
+            INNER JOIN public.product_option o ON o.id = v.option_id
+            WHERE v.id = ANY($1) AND v.store_id = $2
+            ORDER BY o.id, v.id"
+        )
+            .bind::<Array<Integer>, _>(variant_ids)
+            .bind::<Integer, _>(store_id)
+            .get_results::<ProductVariant>(conn)?;
+
+        let mut requested = variant_ids.to_vec();
+        requested.sort_unstable();
+        requested.dedup();
+
+        if variants.len() != requested.len() {
+            return Err(ServiceError::BadRequest {
+                error_message: "Variant not found".to_string(),
+            });
+        }
+
+        Ok(variants)
+    }
+
+    pub fn check_one_value_per_option(variants: &[ProductVariant]) -> Result<(), ServiceError> {
+        for (index, variant) in variants.iter().enumerate() {
+            if variants[..index].iter().any(|other| other.option_id == variant.option_id) {
+                return Err(ServiceError::BadRequest {
+                    error_message: format!("An item can only have one {} value", variant.name),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn combination_key(variants: &[ProductVariant]) -> Vec<i32> {
+        let mut key: Vec<i32> = variants
+            .iter()
+            .map(|variant| variant.id)
+            .collect();
+
+        key.sort_unstable();
+
+        key
+    }
+
+    /// Locks the product row, so checking an item's option combination against its
+    /// siblings and saving it can't interleave with another request doing the same.
+    pub fn lock_combinations(product_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+        use crate::schema::product::dsl;
+
+        dsl::product.filter(dsl::id.eq(product_id)).for_update().execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Option value combinations of the product's items that aren't deleted.
+    pub fn item_combinations(
+        product_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ItemCombination>, ServiceError> {
+        let combinations = sql_query(
+            "SELECT
+            i.id AS product_item_id,
+            COALESCE(
+                array_agg(iv.variant_id ORDER BY iv.variant_id) FILTER (WHERE iv.variant_id IS NOT NULL),
+                '{}'
+            ) AS variant_ids
+            FROM public.product_item i
+            LEFT JOIN public.product_item_variant iv ON iv.product_item_id = i.id
+            WHERE i.product_id = $1 AND i.deleted = false
+            GROUP BY i.id"
+        )
+            .bind::<Integer, _>(product_id)
+            .get_results::<ItemCombination>(conn)?;
+
+        Ok(combinations)
+    }
+
+    /// Items of a product can't share the same option values. Items without any option
+    /// value are left alone, products without options tell them apart by sku.
+    pub fn check_combination(
+        taken_keys: &[Vec<i32>],
+        variants: &[ProductVariant]
+    ) -> Result<(), ServiceError> {
+        if variants.is_empty() {
+            return Ok(());
+        }
+
+        if taken_keys.contains(&Self::combination_key(variants)) {
+            let label: Vec<String> = variants
+                .iter()
+                .map(|variant| format!("{}: {}", variant.name, variant.value))
+                .collect();
+
+            return Err(ServiceError::BadRequest {
+                error_message: format!("Product already has an item with {}", label.join(", ")),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn set_item_variants(
+        product_item_id: &i32,
+        variants: &[ProductVariant],
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_item_variant::dsl;
+
+        diesel
+            ::delete(dsl::product_item_variant)
+            .filter(dsl::product_item_id.eq(product_item_id))
+            .execute(conn)?;
+
+        let new_item_variants: Vec<InsertableItemVariant> = variants
+            .iter()
+            .map(|variant| InsertableItemVariant {
+                product_item_id: product_item_id.to_owned(),
+                variant_id: variant.id,
+                option_id: variant.option_id,
+            })
+            .collect();
+
+        diesel::insert_into(dsl::product_item_variant).values(new_item_variants).execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Option values of each of `product_item_ids`.
+    pub fn item_variants(
+        product_item_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<HashMap<i32, Vec<CatalogVariant>>, ServiceError> {
+        let rows = sql_query(
+            "SELECT iv.product_item_id, v.id, o.name, v.value
+            FROM public.product_item_variant iv
+            INNER JOIN public.product_variant v ON v.id = iv.variant_id
+            INNER JOIN public.product_option o ON o.id = iv.option_id
+            WHERE iv.product_item_id = ANY($1)
+            ORDER BY o.id"
+        )
+            .bind::<Array<Integer>, _>(product_item_ids)
+            .get_results::<ItemVariantRow>(conn)?;
+
+        let mut variants_by_item: HashMap<i32, Vec<CatalogVariant>> = HashMap::new();
+
+        for row in rows {
+            variants_by_item.entry(row.product_item_id).or_default().push(CatalogVariant {
+                id: row.id,
+                name: row.name,
+                value: row.value,
+            });
+        }
+
+        Ok(variants_by_item)
+    }
+
+    /// Uploaded images of each of `product_item_ids`, in display order.
+    pub fn item_images(
+        product_item_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<HashMap<i32, Vec<ItemImage>>, ServiceError> {
+        use crate::schema::{ image, product_item_image };
+
+        let images = product_item_image::table
+            .inner_join(image::table)
+            .filter(product_item_image::product_item_id.eq_any(product_item_ids))
+            .order((product_item_image::product_item_id.asc(), product_item_image::position.asc()))
+            .select((
+                product_item_image::product_item_id,
+                image::id,
+                image::url,
+                image::thumbnail_url,
+                image::width,
+                image::height,
+                product_item_image::position,
+            ))
+            .load::<ItemImage>(conn)?;
+
+        let mut images_by_item: HashMap<i32, Vec<ItemImage>> = HashMap::new();
+
+        for image in images {
+            images_by_item.entry(image.product_item_id).or_default().push(image);
+        }
+
+        Ok(images_by_item)
+    }
+
+    pub fn edit(
+        id: &i32,
+        payload: EditProductBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let found_product = Self::find_by_store(id, store_id, false, conn)?;
+
+        Self::check_category(&payload.category_id, store_id, conn)?;
+
+        let update_result = diesel
+            ::update(dsl::product)
+            .filter(dsl::id.eq(found_product.id))
+            .set((dsl::name.eq(payload.name), dsl::category_id.eq(payload.category_id)))
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn set_deleted(
+        id: &i32,
+        deleted: bool,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let found_product = Self::find_by_store(id, store_id, !deleted, conn)?;
+
+        let update_result = diesel
+            ::update(dsl::product)
+            .filter(dsl::id.eq(found_product.id))
+            .set(dsl::deleted.eq(deleted))
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
         }
     }
 
     pub fn list_variants(
         store_id: &i32,
+        user_id: &i32,
         conn: &mut Connection
     ) -> Result<Vec<ProductVariant>, ServiceError> {
-        let variants_result = sql_query("SELECT * FROM public.product_variant WHERE store_id = $1")
+        StoreService::check_permission(store_id, user_id, Permission::ViewCatalog, conn)?;
+
+        let variants_result = sql_query(
+            "SELECT v.id, v.option_id, o.name, v.value
+            FROM public.product_variant v
+            INNER JOIN public.product_option o ON o.id = v.option_id
+            WHERE v.store_id = $1
+            ORDER BY o.name, v.id"
+        )
             .bind::<Integer, _>(store_id)
             .get_results::<ProductVariant>(conn);
 
@@ -161,19 +1107,274 @@ impl ProductService {
         }
     }
 
+    pub fn list_options(
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ProductOptionDetail>, ServiceError> {
+        use crate::schema::product_option::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ViewCatalog, conn)?;
+
+        let options = dsl::product_option
+            .filter(dsl::store_id.eq(store_id))
+            .order(dsl::name.asc())
+            .load::<ProductOption>(conn)?;
+
+        let mut values_by_option: HashMap<i32, Vec<ProductOptionValue>> = HashMap::new();
+
+        for variant in Self::list_variants(store_id, user_id, conn)? {
+            values_by_option.entry(variant.option_id).or_default().push(ProductOptionValue {
+                id: variant.id,
+                value: variant.value,
+            });
+        }
+
+        Ok(
+            options
+                .into_iter()
+                .map(|option| ProductOptionDetail {
+                    values: values_by_option.remove(&option.id).unwrap_or_default(),
+                    option,
+                })
+                .collect()
+        )
+    }
+
     pub fn list_categories(
         store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<CategoryNode>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ViewCatalog, conn)?;
+
+        Self::category_tree(store_id, conn)
+    }
+
+    pub fn catalog_items(
+        product_ids: &[i32],
         conn: &mut Connection
-    ) -> Result<Vec<ProductCategory>, ServiceError> {
-        let categories_result = sql_query(
-            "SELECT * FROM public.product_category WHERE store_id = $1"
+    ) -> Result<HashMap<i32, Vec<CatalogItem>>, ServiceError> {
+        use crate::schema::{ product_item, product_discount };
+
+        let rows = product_item::table
+            .left_join(
+                product_discount::table.on(
+                    product_item::discount_id
+                        .eq(product_discount::id.nullable())
+                        .and(product_discount::expires_at.gt(diesel::dsl::now))
+                )
+            )
+            .filter(product_item::product_id.eq_any(product_ids))
+            .filter(product_item::deleted.eq(false))
+            .order(product_item::id.asc())
+            .select((
+                product_item::id,
+                product_item::product_id,
+                product_item::sku,
+                product_item::description,
+                product_item::image_url,
+                product_item::price,
+                product_item::stock,
+                product_item::weight_grams,
+                product_discount::percentual.nullable(),
+            ))
+            .load::<CatalogItemRow>(conn)?;
+
+        let item_ids: Vec<i32> = rows
+            .iter()
+            .map(|row| row.id)
+            .collect();
+
+        let reserved: HashMap<i32, i32> = sql_query(
+            "SELECT product_item_id, SUM(quantity)::INTEGER AS reserved
+            FROM public.stock_reservation
+            WHERE product_item_id = ANY($1)
+            AND expires_at > now()
+            GROUP BY product_item_id"
         )
-            .bind::<Integer, _>(store_id)
-            .get_results::<ProductCategory>(conn);
+            .bind::<Array<Integer>, _>(&item_ids)
+            .get_results::<ReservedStock>(conn)?
+            .into_iter()
+            .map(|reserved_stock| (reserved_stock.product_item_id, reserved_stock.reserved))
+            .collect();
 
-        match categories_result {
-            Ok(values) => Ok(values),
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() })
+        let mut variants_by_item = Self::item_variants(&item_ids, conn)?;
+
+        let mut images_by_item = Self::item_images(&item_ids, conn)?;
+
+        let mut items_by_product: HashMap<i32, Vec<CatalogItem>> = HashMap::new();
+
+        for row in rows {
+            let effective_price = match &row.discount_percentual {
+                Some(percentual) => ProductDiscountService::apply_percentual(&row.price, percentual),
+                None => row.price.clone(),
+            };
+
+            let available_stock = (row.stock - reserved.get(&row.id).unwrap_or(&0)).max(0);
+
+            items_by_product.entry(row.product_id).or_default().push(CatalogItem {
+                id: row.id,
+                sku: row.sku,
+                description: row.description,
+                image_url: row.image_url,
+                price: row.price,
+                effective_price,
+                discount_percentual: row.discount_percentual,
+                available_stock,
+                in_stock: available_stock > 0,
+                weight_grams: row.weight_grams,
+                variants: variants_by_item.remove(&row.id).unwrap_or_default(),
+                images: images_by_item.remove(&row.id).unwrap_or_default(),
+            });
         }
+
+        Ok(items_by_product)
+    }
+
+    pub fn list_catalog(
+        store_id: &i32,
+        query: CatalogQuery,
+        conn: &mut Connection
+    ) -> Result<PaginatedResponse<CatalogProductDetail>, ServiceError> {
+        use crate::schema::{ product, product_category };
+
+        validate(&query)?;
+
+        let page = query.page.unwrap_or(1);
+        let per_page = query.per_page.unwrap_or(20);
+
+        let category_id = match (&query.category_slug, query.category_id) {
+            (Some(slug), _) => {
+                let category = product_category::table
+                    .filter(product_category::store_id.eq(store_id))
+                    .filter(product_category::slug.eq(slug))
+                    .first::<ProductCategory>(conn)
+                    .optional()?
+                    .ok_or(ServiceError::NotFound {
+                        error_message: "Category not found".to_string(),
+                    })?;
+
+                Some(category.id)
+            }
+            (None, category_id) => category_id,
+        };
+
+        let category_ids = match category_id {
+            Some(category_id) if query.include_descendants.unwrap_or(false) =>
+                Some(Self::category_subtree_ids(&[category_id], conn)?),
+            Some(category_id) => Some(vec![category_id]),
+            None => None,
+        };
+
+        let build_query = || {
+            let mut filtered = product::table
+                .inner_join(product_category::table)
+                .filter(product::store_id.eq(store_id))
+                .filter(product::deleted.eq(false))
+                .into_boxed();
+
+            if let Some(category_ids) = &category_ids {
+                filtered = filtered.filter(product::category_id.eq_any(category_ids));
+            }
+
+            filtered
+        };
+
+        let total = build_query().count().get_result::<i64>(conn)?;
+
+        let products = build_query()
+            .order(product::id.asc())
+            .limit(per_page)
+            .offset((page - 1) * per_page)
+            .select((
+                product::id,
+                product::name,
+                product::store_id,
+                product::category_id,
+                product_category::name,
+            ))
+            .load::<CatalogProduct>(conn)?;
+
+        let product_ids: Vec<i32> = products
+            .iter()
+            .map(|found_product| found_product.id)
+            .collect();
+
+        let mut items_by_product = Self::catalog_items(&product_ids, conn)?;
+
+        let items = products
+            .into_iter()
+            .map(|found_product| CatalogProductDetail {
+                items: items_by_product.remove(&found_product.id).unwrap_or_default(),
+                product: found_product,
+            })
+            .collect();
+
+        Ok(PaginatedResponse { items, total, page, per_page })
+    }
+
+    pub fn find_catalog(
+        id: &i32,
+        conn: &mut Connection
+    ) -> Result<CatalogProductDetail, ServiceError> {
+        use crate::schema::{ product, product_category };
+
+        let found_product = product::table
+            .inner_join(product_category::table)
+            .filter(product::id.eq(id))
+            .filter(product::deleted.eq(false))
+            .select((
+                product::id,
+                product::name,
+                product::store_id,
+                product::category_id,
+                product_category::name,
+            ))
+            .first::<CatalogProduct>(conn)
+            .map_err(|_| ServiceError::NotFound { error_message: "Product not found".to_string() })?;
+
+        let items = Self::catalog_items(&[found_product.id], conn)?
+            .remove(&found_product.id)
+            .unwrap_or_default();
+
+        Ok(CatalogProductDetail { product: found_product, items })
+    }
+
+    /// Catalog details for `product_ids`, keeping the order they were given in.
+    pub fn catalog_details(
+        product_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<Vec<CatalogProductDetail>, ServiceError> {
+        use crate::schema::{ product, product_category };
+
+        let mut products: HashMap<i32, CatalogProduct> = product::table
+            .inner_join(product_category::table)
+            .filter(product::id.eq_any(product_ids))
+            .filter(product::deleted.eq(false))
+            .select((
+                product::id,
+                product::name,
+                product::store_id,
+                product::category_id,
+                product_category::name,
+            ))
+            .load::<CatalogProduct>(conn)?
+            .into_iter()
+            .map(|found_product| (found_product.id, found_product))
+            .collect();
+
+        let mut items_by_product = Self::catalog_items(product_ids, conn)?;
+
+        Ok(
+            product_ids
+                .iter()
+                .filter_map(|product_id| products.remove(product_id))
+                .map(|found_product| CatalogProductDetail {
+                    items: items_by_product.remove(&found_product.id).unwrap_or_default(),
+                    product: found_product,
+                })
+                .collect()
+        )
     }
 }
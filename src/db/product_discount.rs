@@ -0,0 +1,227 @@
+use std::time::SystemTime;
+
+use bigdecimal::{ BigDecimal, FromPrimitive };
+use crate::{
+    controllers::product::{ DiscountBody, AttachDiscountBody },
+    models::{
+        product::{ ProductDiscount, InsertableProductDiscount, ProductItem },
+        response::IDResponse,
+        role::Permission,
+    },
+    error::ServiceError,
+    utils::validation::validate,
+    db::store::StoreService,
+};
+use super::Connection;
+use diesel::sql_types::{ Integer, Nullable, Numeric, Timestamp };
+use diesel::{ prelude::*, sql_query };
+
+pub struct ProductDiscountService;
+
+impl ProductDiscountService {
+    pub fn find_active(
+        id: &i32,
+        conn: &mut Connection
+    ) -> Result<Option<ProductDiscount>, ServiceError> {
+        let discount = sql_query(
+            "SELECT * FROM public.product_discount WHERE id = $1 AND expires_at > now()"
+        )
+            .bind::<Integer, _>(id)
+            .get_result::<ProductDiscount>(conn)
+            .optional()?;
+
+        Ok(discount)
+    }
+
+    pub fn find(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ProductDiscount, ServiceError> {
+        let discount_result = sql_query(
+            "SELECT * FROM public.product_discount WHERE id = $1 AND store_id = $2"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<ProductDiscount>(conn);
+
+        match discount_result {
+            Ok(discount) => Ok(discount),
+            Err(_) =>
+                Err(ServiceError::NotFound { error_message: "Discount not found".to_string() }),
+        }
+    }
+
+    /// Price of `product_item` after applying its discount, if it hasn't expired yet.
+    pub fn effective_price(
+        product_item: &ProductItem,
+        conn: &mut Connection
+    ) -> Result<BigDecimal, ServiceError> {
+        let active_discount = match product_item.discount_id {
+            Some(discount_id) => Self::find_active(&discount_id, conn)?,
+            None => None,
+        };
+
+        match active_discount {
+            Some(discount) =>
+                Ok(Self::apply_percentual(&product_item.price, &discount.percentual)),
+            None => Ok(product_item.price.clone()),
+        }
+    }
+
+    pub fn apply_percentual(price: &BigDecimal, percentual: &BigDecimal) -> BigDecimal {
+        let hundred = BigDecimal::from(100);
+
+        ((price * (&hundred - percentual)) / hundred).round(2)
+    }
+
+    fn check_expiration(payload: &DiscountBody, conn: &mut Connection) -> Result<(), ServiceError> {
+        let now = diesel::select(diesel::dsl::now).get_result::<SystemTime>(conn)?;
+
+        if SystemTime::from(payload.expires_at.and_utc()) <= now {
+            return Err(ServiceError::BadRequest {
+                error_message: "Discount expiration must be in the future".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn create(
+        payload: DiscountBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        use crate::schema::product_discount;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        Self::check_expiration(&payload, conn)?;
+
+        let new_discount = InsertableProductDiscount {
+            percentual: BigDecimal::from_f32(payload.percentual).unwrap().round(2),
+            expires_at: payload.expires_at,
+            store_id: store_id.to_owned(),
+        };
+
+        match
+            diesel
+                ::insert_into(product_discount::dsl::product_discount)
+                .values(new_discount)
+                .returning(product_discount::dsl::id)
+                .get_result::<i32>(conn)
+        {
+            Ok(id) => Ok(IDResponse { id }),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn list(
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ProductDiscount>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let discounts_result = sql_query(
+            "SELECT * FROM public.product_discount WHERE store_id = $1 ORDER BY expires_at DESC"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<ProductDiscount>(conn);
+
+        match discounts_result {
+            Ok(discounts) => Ok(discounts),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn edit(
+        id: &i32,
+        payload: DiscountBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let discount = Self::find(id, store_id, conn)?;
+
+        Self::check_expiration(&payload, conn)?;
+
+        let update_result = sql_query(
+            "UPDATE public.product_discount SET percentual = $1, expires_at = $2 WHERE id = $3"
+        )
+            .bind::<Numeric, _>(BigDecimal::from_f32(payload.percentual).unwrap().round(2))
+            .bind::<Timestamp, _>(payload.expires_at)
+            .bind::<Integer, _>(discount.id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn delete(
+        id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_discount;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let discount = Self::find(id, store_id, conn)?;
+
+        let delete_result = diesel
+            ::delete(product_discount::dsl::product_discount)
+            .filter(product_discount::dsl::id.eq(discount.id))
+            .execute(conn);
+
+        match delete_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn attach_to_item(
+        product_item_id: &i32,
+        payload: AttachDiscountBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        if let Some(discount_id) = payload.discount_id {
+            Self::find(&discount_id, store_id, conn)?;
+        }
+
+        let update_result = sql_query(
+            "UPDATE public.product_item 
+            SET discount_id = $1, updated_at = now() 
+            WHERE id = $2 
+            AND store_id = $3 
+            AND deleted = false"
+        )
+            .bind::<Nullable<Integer>, _>(payload.discount_id)
+            .bind::<Integer, _>(product_item_id)
+            .bind::<Integer, _>(store_id)
+            .execute(conn);
+
+        match update_result {
+            Ok(0) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Product item not found!".to_string(),
+                }),
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
@@ -0,0 +1,624 @@
+use std::{ collections::{ HashMap, HashSet }, str::FromStr };
+
+use bigdecimal::{ BigDecimal, Zero };
+use csv::{ ErrorKind, ReaderBuilder, StringRecord, Trim, Writer };
+use diesel::{
+    prelude::*,
+    result::Error as DieselError,
+    sql_query,
+    sql_types::{ Integer, Text },
+    Connection as _,
+};
+use crate::{
+    controllers::product::ProductCsvRow,
+    models::{
+        product::{
+            CategoryId,
+            ProductItem,
+            ProductOption,
+            ProductVariant,
+            InsertableProduct,
+            InsertableProductItem,
+            InsertableProductOption,
+            InsertableVariant,
+            ImportReport,
+            ImportRowError,
+            ExportItemRow,
+        },
+        role::Permission,
+    },
+    error::ServiceError,
+    utils::{ upload::UploadedFile, validation::validate },
+};
+use super::{
+    Connection,
+    store::StoreService,
+    product::ProductService,
+    product_item::ProductItemService,
+};
+
+const COLUMNS: [&str; 8] = [
+    "name",
+    "category",
+    "sku",
+    "description",
+    "price",
+    "stock",
+    "variant",
+    "image",
+];
+const MAX_IMPORT_ROWS: usize = 10000;
+
+/// Line number and sku of a CSV row, with the row or what's wrong with it.
+type ParsedRow = (u64, Option<String>, Result<ProductCsvRow, String>);
+
+enum ImportedRow {
+    Created,
+    Updated,
+}
+
+pub struct ProductImportService;
+
+impl ProductImportService {
+    fn row_error(message: String) -> ServiceError {
+        ServiceError::BadRequest { error_message: message }
+    }
+
+    /// Parses the CSV rows, keeping each row's line number and its parse or validation error.
+    fn parse(bytes: &[u8]) -> Result<Vec<ParsedRow>, ServiceError> {
+        let mut reader = ReaderBuilder::new().trim(Trim::All).from_reader(bytes);
+
+        let headers: StringRecord = reader
+            .headers()
+            .map_err(|e| Self::row_error(format!("Invalid CSV header: {}", e)))?
+            .clone();
+
+        if let Some(missing) = COLUMNS.iter().find(|column| !headers.iter().any(|h| h == **column)) {
+            return Err(Self::row_error(format!("Missing {} column", missing)));
+        }
+
+        let sku_column = headers.iter().position(|header| header == "sku");
+
+        let mut rows = Vec::new();
+
+        for result in reader.records() {
+            if rows.len() == MAX_IMPORT_ROWS {
+                return Err(
+                    Self::row_error(format!("Imports are limited to {} rows", MAX_IMPORT_ROWS))
+                );
+            }
+
+            let record = result.map_err(|e| Self::row_error(format!("Invalid CSV: {}", e)))?;
+
+            let line = record
+                .position()
+                .map(|position| position.line())
+                .unwrap_or_default();
+
+            let sku = sku_column
+                .and_then(|column| record.get(column))
+                .filter(|sku| !sku.is_empty())
+                .map(str::to_string);
+
+            let row = match record.deserialize::<ProductCsvRow>(Some(&headers)) {
+                Ok(row) =>
+                    validate(&row)
+                        .map(|_| row)
+                        .map_err(|e| e.to_string()),
+                Err(e) =>
+                    match e.kind() {
+                        ErrorKind::Deserialize { err, .. } => {
+                            let column = err
+                                .field()
+                                .and_then(|field| headers.get(field as usize))
+                                .unwrap_or("row");
+
+                            Err(format!("Invalid {}: {}", column, err.kind()))
+                        }
+                        _ => Err(e.to_string()),
+                    }
+            };
+
+            rows.push((line, sku, row));
+        }
+
+        Ok(rows)
+    }
+
+    /// Finds a category of the store by slug, or by name when no slug matches.
+    fn find_category(
+        category: &str,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<i32, ServiceError> {
+        use crate::schema::product_category::dsl;
+
+        let by_slug = dsl::product_category
+            .filter(dsl::store_id.eq(store_id))
+            .filter(dsl::slug.eq(category))
+            .select(dsl::id)
+            .first::<i32>(conn)
+            .optional()?;
+
+        if let Some(category_id) = by_slug {
+            return Ok(category_id);
+        }
+
+        let by_name = sql_query(
+            "SELECT id FROM public.product_category WHERE store_id = $1 AND lower(name) = lower($2)"
+        )
+            .bind::<Integer, _>(store_id)
+            .bind::<Text, _>(category)
+            .get_results::<CategoryId>(conn)?;
+
+        match by_name.as_slice() {
+            [found] => Ok(found.id),
+            [] => Err(Self::row_error(format!("Category {} not found", category))),
+            _ =>
+                Err(
+                    Self::row_error(format!("Several categories are called {}, use a slug", category))
+                ),
+        }
+    }
+
+    /// Option values named by a `Size: M; Color: Red` cell, creating the missing ones.
+    fn find_or_create_variants(
+        cell: &str,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ProductVariant>, ServiceError> {
+        use crate::schema::{ product_option, product_variant };
+
+        let mut variant_ids = Vec::new();
+
+        for pair in cell.split(';').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, value) = match pair.split_once(':') {
+                Some((name, value)) if !name.trim().is_empty() && !value.trim().is_empty() =>
+                    (name.trim(), value.trim()),
+                _ => {
+                    return Err(
+                        Self::row_error(format!("Invalid variant {}, expected Option: Value", pair))
+                    );
+                }
+            };
+
+            if name.chars().count() < 2 || name.chars().count() > 60 || value.chars().count() > 60 {
+                return Err(Self::row_error(format!("Invalid variant {}", pair)));
+            }
+
+            if value.contains(':') {
+                return Err(
+                    Self::row_error(format!("Invalid variant {}, values can't contain ':'", pair))
+                );
+            }
+
+            let existing_option = product_option::dsl::product_option
+                .filter(product_option::dsl::name.eq(name))
+                .filter(product_option::dsl::store_id.eq(store_id))
+                .first::<ProductOption>(conn)
+                .optional()?;
+
+            let option = match existing_option {
+                Some(option) => option,
+                None =>
+                    diesel
+                        ::insert_into(product_option::dsl::product_option)
+                        .values(InsertableProductOption {
+                            name: name.to_string(),
+                            store_id: store_id.to_owned(),
+                        })
+                        .get_result::<ProductOption>(conn)?,
+            };
+
+            let existing_value = product_variant::dsl::product_variant
+                .filter(product_variant::dsl::option_id.eq(option.id))
+                .filter(product_variant::dsl::value.eq(value))
+                .select(product_variant::dsl::id)
+                .first::<i32>(conn)
+                .optional()?;
+
+            let variant_id = match existing_value {
+                Some(variant_id) => variant_id,
+                None =>
+                    diesel
+                        ::insert_into(product_variant::dsl::product_variant)
+                        .values(InsertableVariant {
+                            value: value.to_string(),
+                            store_id: store_id.to_owned(),
+                            option_id: option.id,
+                        })
+                        .returning(product_variant::dsl::id)
+                        .get_result::<i32>(conn)?,
+            };
+
+            variant_ids.push(variant_id);
+        }
+
+        let variants = ProductService::find_variants(&variant_ids, store_id, conn)?;
+
+        ProductService::check_one_value_per_option(&variants)?;
+
+        Ok(variants)
+    }
+
+    /// Not deleted items of the store with the given sku.
+    fn find_items_by_sku(
+        sku: &str,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ProductItem>, ServiceError> {
+        let items = sql_query(
+            "SELECT i.* FROM public.product_item i
+            INNER JOIN public.product p ON p.id = i.product_id
+            WHERE i.store_id = $1 AND i.sku = $2 AND i.deleted = false AND p.deleted = false"
+        )
+            .bind::<Integer, _>(store_id)
+            .bind::<Text, _>(sku)
+            .get_results::<ProductItem>(conn)?;
+
+        Ok(items)
+    }
+
+    fn import_row(
+        row: ProductCsvRow,
+        store_id: &i32,
+        products_by_name: &HashMap<String, i32>,
+        conn: &mut Connection
+    ) -> Result<(ImportedRow, String, i32), ServiceError> {
+        use crate::schema::{ product, product_item };
+
+        let price = match BigDecimal::from_str(&row.price) {
+            Ok(price) if price >= BigDecimal::zero() => price.round(2),
+            _ => {
+                return Err(Self::row_error("Invalid price: must be a number of at least 0".to_string()));
+            }
+        };
+
+        let category_id = Self::find_category(&row.category, store_id, conn)?;
+
+        let variants = match &row.variant {
+            Some(cell) => Self::find_or_create_variants(cell, store_id, conn)?,
+            None => Vec::new(),
+        };
+
+        let existing_item = match &row.sku {
+            Some(sku) =>
+                match Self::find_items_by_sku(sku, store_id, conn)?.as_slice() {
+                    [] => None,
+                    [item] => Some(item.id),
+                    _ => {
+                        return Err(Self::row_error(format!("Several items have the sku {}", sku)));
+                    }
+                }
+            None => None,
+        };
+
+        if let Some(item_id) = existing_item {
+            let item = ProductItemService::find(&item_id, false, conn)?;
+
+            ProductService::lock_combinations(&item.product_id, conn)?;
+
+            ProductService::check_combination(
+                &ProductItemService::taken_combinations(&item, conn)?,
+                &variants
+            )?;
+
+            diesel
+                ::update(product::dsl::product)
+                .filter(product::dsl::id.eq(item.product_id))
+                .set((product::dsl::name.eq(&row.name), product::dsl::category_id.eq(category_id)))
+                .execute(conn)?;
+
+            diesel
+                ::update(product_item::dsl::product_item)
+                .filter(product_item::dsl::id.eq(item.id))
+                .set((
+                    product_item::dsl::description.eq(row.description),
+                    product_item::dsl::image_url.eq(row.image),
+                    product_item::dsl::price.eq(price),
+                    product_item::dsl::stock.eq(row.stock),
+                    product_item::dsl::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+
+            ProductService::set_item_variants(&item.id, &variants, conn)?;
+
+            return Ok((ImportedRow::Updated, row.name, item.product_id));
+        }
+
+        let product_id = match products_by_name.get(&row.name) {
+            Some(product_id) => {
+                diesel
+                    ::update(product::dsl::product)
+                    .filter(product::dsl::id.eq(product_id))
+                    .set(product::dsl::category_id.eq(category_id))
+                    .execute(conn)?;
+
+                product_id.to_owned()
+            }
+            None =>
+                diesel
+                    ::insert_into(product::dsl::product)
+                    .values(InsertableProduct {
+                        name: row.name.clone(),
+                        category_id,
+                        store_id: store_id.to_owned(),
+                    })
+                    .returning(product::dsl::id)
+                    .get_result::<i32>(conn)?,
+        };
+
+        ProductService::lock_combinations(&product_id, conn)?;
+
+        let taken_keys: Vec<Vec<i32>> = ProductService::item_combinations(&product_id, conn)?
+            .into_iter()
+            .map(|combination| combination.variant_ids)
+            .collect();
+
+        ProductService::check_combination(&taken_keys, &variants)?;
+
+        let item_id = diesel
+            ::insert_into(product_item::dsl::product_item)
+            .values(InsertableProductItem {
+                description: row.description,
+                image_url: row.image,
+                sku: row.sku,
+                price,
+                stock: row.stock,
+                weight_grams: None,
+                store_id: store_id.to_owned(),
+                product_id,
+            })
+            .returning(product_item::dsl::id)
+            .get_result::<i32>(conn)?;
+
+        ProductService::set_item_variants(&item_id, &variants, conn)?;
+
+        Ok((ImportedRow::Created, row.name, product_id))
+    }
+
+    /// Creates or updates an item per row, matching items by sku. Only rows of new products
+    /// may leave the sku out. Rows are all checked against the database, but nothing is saved
+    /// on a dry run or when any row fails.
+    pub fn import(
+        file: UploadedFile,
+        dry_run: bool,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ImportReport, ServiceError> {
+        use crate::schema::product::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let rows = Self::parse(&file.bytes)?;
+
+        let mut report = ImportReport {
+            dry_run,
+            imported: false,
+            created: 0,
+            updated: 0,
+            errors: Vec::new(),
+        };
+
+        let transaction_result = conn.transaction::<_, ServiceError, _>(|conn| {
+            let mut products_by_name: HashMap<String, i32> = HashMap::new();
+
+            // the oldest product wins when several share a name
+            for (id, name) in dsl::product
+                .filter(dsl::store_id.eq(store_id))
+                .filter(dsl::deleted.eq(false))
+                .order(dsl::id.desc())
+                .select((dsl::id, dsl::name))
+                .load::<(i32, String)>(conn)? {
+                products_by_name.insert(name, id);
+            }
+
+            // sku-less rows can't tell their item apart from the existing ones, importing an
+            // export again would duplicate every such item
+            let existing_names: HashSet<String> = products_by_name.keys().cloned().collect();
+
+            let mut seen_skus: HashSet<String> = HashSet::new();
+
+            for (line, sku, row) in rows {
+                let row = match row {
+                    Ok(row) => row,
+                    Err(message) => {
+                        report.errors.push(ImportRowError { line, sku, message });
+                        continue;
+                    }
+                };
+
+                let sku = row.sku.clone();
+
+                if sku.is_none() && existing_names.contains(&row.name) {
+                    report.errors.push(ImportRowError {
+                        line,
+                        sku: None,
+                        message: "A sku is required for items of existing products".to_string(),
+                    });
+                    continue;
+                }
+
+                if let Some(sku) = &sku {
+                    if !seen_skus.insert(sku.clone()) {
+                        report.errors.push(ImportRowError {
+                            line,
+                            sku: Some(sku.clone()),
+                            message: "The sku is repeated in the file".to_string(),
+                        });
+                        continue;
+                    }
+                }
+
+                // a savepoint per row, so a failed row doesn't abort the rest of the check
+                match
+                    conn.transaction(|conn| Self::import_row(row, store_id, &products_by_name, conn))
+                {
+                    Ok((imported, name, product_id)) => {
+                        products_by_name.insert(name, product_id);
+
+                        match imported {
+                            ImportedRow::Created => {
+                                report.created += 1;
+                            }
+                            ImportedRow::Updated => {
+                                report.updated += 1;
+                            }
+                        }
+                    }
+                    Err(e) =>
+                        report.errors.push(ImportRowError {
+                            line,
+                            sku,
+                            message: e.to_string(),
+                        }),
+                }
+            }
+
+            if dry_run || !report.errors.is_empty() {
+                return Err(DieselError::RollbackTransaction.into());
+            }
+
+            Ok(())
+        });
+
+        match transaction_result {
+            Ok(_) => {
+                report.imported = true;
+                Ok(report)
+            }
+            Err(_) if dry_run || !report.errors.is_empty() => Ok(report),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Every item of the store's products in the import format, categories by slug.
+    pub fn export(
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<u8>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ViewCatalog, conn)?;
+
+        let items = sql_query(
+            "SELECT i.id, p.name, c.slug AS category, i.sku, i.description, i.price, i.stock, i.image_url
+            FROM public.product_item i
+            INNER JOIN public.product p ON p.id = i.product_id
+            INNER JOIN public.product_category c ON c.id = p.category_id
+            WHERE i.store_id = $1 AND i.deleted = false AND p.deleted = false
+            ORDER BY p.id, i.id"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<ExportItemRow>(conn)?;
+
+        let item_ids: Vec<i32> = items
+            .iter()
+            .map(|item| item.id)
+            .collect();
+
+        let mut variants_by_item = ProductService::item_variants(&item_ids, conn)?;
+
+        let mut writer = Writer::from_writer(Vec::new());
+
+        let write_error = |e: csv::Error| ServiceError::InternalServerError {
+            error_message: e.to_string(),
+        };
+
+        // an empty export still tells merchants which columns to fill
+        if items.is_empty() {
+            writer.write_record(COLUMNS).map_err(write_error)?;
+        }
+
+        for item in items {
+            let variant: Vec<String> = variants_by_item
+                .remove(&item.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|variant| format!("{}: {}", variant.name, variant.value))
+                .collect();
+
+            writer
+                .serialize(ProductCsvRow {
+                    name: item.name,
+                    category: item.category,
+                    sku: item.sku,
+                    description: item.description,
+                    price: item.price.to_string(),
+                    stock: item.stock,
+                    variant: Some(variant.join("; ")).filter(|variant| !variant.is_empty()),
+                    image: item.image_url,
+                })
+                .map_err(write_error)?;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|e| ServiceError::InternalServerError { error_message: e.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "name,category,sku,description,price,stock,variant,image\n";
+
+    fn parse(body: &str) -> Result<Vec<ParsedRow>, ServiceError> {
+        ProductImportService::parse(format!("{}{}", HEADER, body).as_bytes())
+    }
+
+    #[test]
+    fn missing_column_is_rejected() {
+        let result = ProductImportService::parse(b"name,category,sku,price,stock\n");
+
+        assert!(
+            matches!(result, Err(ServiceError::BadRequest { error_message }) if error_message == "Missing description column")
+        );
+    }
+
+    #[test]
+    fn cells_are_trimmed_and_empty_sku_is_none() {
+        let rows = parse(" T-Shirt , shirts ,  , Cotton , 9.90 , 3 ,Size: M, \n").unwrap();
+
+        let (line, sku, row) = &rows[0];
+        let row = row.as_ref().unwrap();
+
+        assert_eq!(*line, 2);
+        assert_eq!(*sku, None);
+        assert_eq!(row.sku, None);
+        assert_eq!(row.name, "T-Shirt");
+        assert_eq!(row.category, "shirts");
+        assert_eq!(row.price, "9.90");
+        assert_eq!(row.stock, 3);
+        assert_eq!(row.variant.as_deref(), Some("Size: M"));
+        assert_eq!(row.image, None);
+    }
+
+    #[test]
+    fn invalid_stock_is_reported_with_its_line_and_sku() {
+        let rows = parse("Mug,kitchen,MUG-1,,4.50,3,,\nMug,kitchen,MUG-2,,4.50,many,,\n").unwrap();
+
+        assert!(rows[0].2.is_ok());
+
+        let (line, sku, row) = &rows[1];
+
+        assert_eq!(*line, 3);
+        assert_eq!(sku.as_deref(), Some("MUG-2"));
+        assert!(row.as_ref().err().unwrap().starts_with("Invalid stock"));
+    }
+
+    #[test]
+    fn failed_validation_is_reported_per_row() {
+        let rows = parse("M,kitchen,MUG-1,,4.50,-1,,\n").unwrap();
+
+        assert!(rows[0].2.is_err());
+    }
+
+    #[test]
+    fn malformed_price_is_left_for_the_import() {
+        let rows = parse("Mug,kitchen,MUG-1,,cheap,3,,\n").unwrap();
+
+        assert_eq!(rows[0].2.as_ref().unwrap().price, "cheap");
+    }
+}
@@ -1,7 +1,32 @@
-use crate::{ models::product::ProductItem, error::ServiceError };
-use super::Connection;
+use bigdecimal::{ BigDecimal, FromPrimitive };
+use crate::{
+    controllers::product::{ EditProductItemBody, GenerateItemsBody, ReorderImagesBody },
+    models::{
+        image::{ Image, InsertableItemImage, ItemImage },
+        product::{
+            ProductItem,
+            ProductItemChangeset,
+            InsertableProductItem,
+            ProductVariant,
+            GeneratedItems,
+        },
+        role::Permission,
+    },
+    error::ServiceError,
+    storage::FileStorage,
+    utils::{
+        validation::validate,
+        slug::slugify,
+        upload::{ process_image, ProcessedImage, UploadedFile },
+    },
+};
+use super::{ Connection, store::StoreService, product::ProductService, image::ImageService };
 use diesel::sql_types::{ Integer, Bool };
-use diesel::{ prelude::*, sql_query };
+use diesel::{ prelude::*, sql_query, Connection as _ };
+
+/// Upper bound on the items a single matrix generation may create.
+const MAX_GENERATED_ITEMS: usize = 500;
+pub const MAX_ITEM_IMAGES: usize = 12;
 
 pub struct ProductItemService;
 
@@ -26,4 +51,514 @@ impl ProductItemService {
                 }),
         }
     }
+
+    pub fn find_for_update(id: &i32, conn: &mut Connection) -> Result<ProductItem, ServiceError> {
+        let product_item_result = sql_query(
+            "SELECT i.* FROM public.product_item i
+            INNER JOIN public.product p ON p.id = i.product_id
+            WHERE i.id = $1
+            AND i.deleted = false
+            AND p.deleted = false
+            FOR UPDATE OF i"
+        )
+            .bind::<Integer, _>(id)
+            .get_result::<ProductItem>(conn);
+
+        match product_item_result {
+            Ok(product_item) => Ok(product_item),
+            Err(_) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Product item not found!".to_string(),
+                }),
+        }
+    }
+
+    pub fn decrement_stock(
+        id: &i32,
+        quantity: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query(
+            "UPDATE public.product_item SET stock = stock - $1, updated_at = now()
+            WHERE id = $2 AND $1 > 0 AND stock >= $1"
+        )
+            .bind::<Integer, _>(quantity)
+            .bind::<Integer, _>(id)
+            .execute(conn);
+
+        match update_result {
+            Ok(0) =>
+                Err(ServiceError::BadRequest {
+                    error_message: "Quantity not available".to_string(),
+                }),
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn increment_stock(
+        id: &i32,
+        quantity: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query(
+            "UPDATE public.product_item SET stock = stock + $1, updated_at = now() WHERE id = $2"
+        )
+            .bind::<Integer, _>(quantity)
+            .bind::<Integer, _>(id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    fn find_by_product(
+        id: &i32,
+        product_id: &i32,
+        store_id: &i32,
+        deleted: bool,
+        conn: &mut Connection
+    ) -> Result<ProductItem, ServiceError> {
+        let product_item = Self::find(id, deleted, conn)?;
+
+        if product_item.product_id != *product_id || product_item.store_id != *store_id {
+            return Err(ServiceError::NotFound {
+                error_message: "Product item not found!".to_string(),
+            });
+        }
+
+        Ok(product_item)
+    }
+
+    pub fn edit(
+        id: &i32,
+        product_id: &i32,
+        payload: EditProductItemBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_item::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let product_item = Self::find_by_product(id, product_id, store_id, false, conn)?;
+
+        let variants = match &payload.variant_ids {
+            Some(variant_ids) => {
+                let variants = ProductService::find_variants(variant_ids, store_id, conn)?;
+
+                ProductService::check_one_value_per_option(&variants)?;
+
+                Some(variants)
+            }
+            None => None,
+        };
+
+        let changeset = ProductItemChangeset {
+            sku: payload.sku,
+            description: payload.description,
+            price: payload.price.and_then(BigDecimal::from_f32),
+            stock: payload.stock,
+            image_url: payload.image,
+            weight_grams: payload.weight_grams,
+        };
+
+        conn.transaction(|conn| {
+            if let Some(variants) = &variants {
+                ProductService::lock_combinations(&product_item.product_id, conn)?;
+
+                ProductService::check_combination(
+                    &Self::taken_combinations(&product_item, conn)?,
+                    variants
+                )?;
+            }
+
+            diesel
+                ::update(dsl::product_item)
+                .filter(dsl::id.eq(product_item.id))
+                .set((changeset, dsl::updated_at.eq(diesel::dsl::now)))
+                .execute(conn)?;
+
+            if let Some(variants) = variants {
+                ProductService::set_item_variants(&product_item.id, &variants, conn)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Option value combinations used by the other items of the product.
+    pub fn taken_combinations(
+        product_item: &ProductItem,
+        conn: &mut Connection
+    ) -> Result<Vec<Vec<i32>>, ServiceError> {
+        Ok(
+            ProductService::item_combinations(&product_item.product_id, conn)?
+                .into_iter()
+                .filter(|combination| combination.product_item_id != product_item.id)
+                .map(|combination| combination.variant_ids)
+                .collect()
+        )
+    }
+
+    /// Creates an item for every combination of the selected option values that the
+    /// product doesn't have yet.
+    pub fn generate(
+        product_id: &i32,
+        payload: GenerateItemsBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<GeneratedItems, ServiceError> {
+        use crate::schema::product_item::dsl;
+
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let found_product = ProductService::find_by_store(product_id, store_id, false, conn)?;
+
+        let variants = ProductService::find_variants(&payload.variant_ids, store_id, conn)?;
+
+        // find_variants orders by option type, so each option's values are contiguous
+        let mut options: Vec<Vec<ProductVariant>> = Vec::new();
+
+        for variant in variants {
+            match options.last_mut() {
+                Some(values) if values[0].option_id == variant.option_id => values.push(variant),
+                _ => options.push(vec![variant]),
+            }
+        }
+
+        let matrix_size = options.iter().fold(1usize, |size, values| size.saturating_mul(values.len()));
+
+        if matrix_size > MAX_GENERATED_ITEMS {
+            return Err(ServiceError::BadRequest {
+                error_message: format!(
+                    "Selection would create {} items, the limit is {}",
+                    matrix_size,
+                    MAX_GENERATED_ITEMS
+                ),
+            });
+        }
+
+        let mut combinations: Vec<Vec<ProductVariant>> = vec![Vec::new()];
+
+        for values in &options {
+            combinations = combinations
+                .iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut next = combination.clone();
+                        next.push(value.clone());
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        let price = BigDecimal::from_f32(payload.price).unwrap().round(2);
+
+        conn.transaction(|conn| {
+            ProductService::lock_combinations(&found_product.id, conn)?;
+
+            let mut taken_keys: Vec<Vec<i32>> = ProductService::item_combinations(&found_product.id, conn)?
+                .into_iter()
+                .map(|combination| combination.variant_ids)
+                .collect();
+
+            let mut created = Vec::new();
+            let mut skipped = 0;
+
+            for combination in combinations {
+                let key = ProductService::combination_key(&combination);
+
+                if taken_keys.contains(&key) {
+                    skipped += 1;
+                    continue;
+                }
+
+                let values: Vec<&str> = combination
+                    .iter()
+                    .map(|variant| variant.value.as_str())
+                    .collect();
+
+                let sku = payload.sku_prefix.as_ref().map(|prefix| {
+                    let suffix: Vec<String> = values
+                        .iter()
+                        .map(|value| slugify(value).to_uppercase())
+                        .collect();
+
+                    format!("{}-{}", prefix.trim(), suffix.join("-"))
+                });
+
+                let item_id = diesel
+                    ::insert_into(dsl::product_item)
+                    .values(InsertableProductItem {
+                        sku,
+                        description: Some(format!("{} - {}", found_product.name, values.join(" / "))),
+                        price: price.clone(),
+                        stock: payload.stock,
+                        image_url: None,
+                        product_id: found_product.id,
+                        store_id: store_id.to_owned(),
+                        weight_grams: payload.weight_grams,
+                    })
+                    .returning(dsl::id)
+                    .get_result::<i32>(conn)?;
+
+                ProductService::set_item_variants(&item_id, &combination, conn)?;
+
+                taken_keys.push(key);
+                created.push(item_id);
+            }
+
+            Ok(GeneratedItems { created, skipped })
+        })
+    }
+
+    pub fn set_deleted(
+        id: &i32,
+        product_id: &i32,
+        deleted: bool,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::product_item::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let product_item = Self::find_by_product(id, product_id, store_id, !deleted, conn)?;
+
+        conn.transaction(|conn| {
+            if !deleted {
+                let variant_ids: Vec<i32> = ProductService::item_variants(&[product_item.id], conn)?
+                    .remove(&product_item.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|variant| variant.id)
+                    .collect();
+
+                ProductService::lock_combinations(&product_item.product_id, conn)?;
+
+                ProductService::check_combination(
+                    &Self::taken_combinations(&product_item, conn)?,
+                    &ProductService::find_variants(&variant_ids, store_id, conn)?
+                )?;
+            }
+
+            diesel
+                ::update(dsl::product_item)
+                .filter(dsl::id.eq(product_item.id))
+                .set((dsl::deleted.eq(deleted), dsl::updated_at.eq(diesel::dsl::now)))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    /// Locks the item so concurrent image changes assign positions one at a time.
+    fn lock_images(id: &i32, conn: &mut Connection) -> Result<Vec<i32>, ServiceError> {
+        use crate::schema::{ product_item, product_item_image::dsl };
+
+        product_item::dsl::product_item
+            .find(id)
+            .select(product_item::dsl::id)
+            .for_update()
+            .first::<i32>(conn)?;
+
+        let image_ids = dsl::product_item_image
+            .filter(dsl::product_item_id.eq(id))
+            .order(dsl::position.asc())
+            .select(dsl::image_id)
+            .load::<i32>(conn)?;
+
+        Ok(image_ids)
+    }
+
+    /// Numbers the item's images in the given order and mirrors the first one into
+    /// `product_item.image_url`, which is what carts and orders show.
+    fn set_image_order(id: &i32, image_ids: &[i32], conn: &mut Connection) -> Result<(), ServiceError> {
+        use crate::schema::{ image, product_item, product_item_image::dsl };
+
+        for (position, image_id) in image_ids.iter().enumerate() {
+            diesel
+                ::update(dsl::product_item_image)
+                .filter(dsl::product_item_id.eq(id))
+                .filter(dsl::image_id.eq(image_id))
+                .set(dsl::position.eq(position as i32))
+                .execute(conn)?;
+        }
+
+        let image_url = match image_ids.first() {
+            Some(image_id) =>
+                Some(
+                    image::dsl::image
+                        .find(image_id)
+                        .select(image::dsl::url)
+                        .first::<String>(conn)?
+                ),
+            None => None,
+        };
+
+        diesel
+            ::update(product_item::dsl::product_item)
+            .filter(product_item::dsl::id.eq(id))
+            .set((
+                product_item::dsl::image_url.eq(image_url),
+                product_item::dsl::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Uploads images to the end of the item's gallery.
+    pub fn add_images(
+        id: &i32,
+        product_id: &i32,
+        files: Vec<UploadedFile>,
+        user_id: &i32,
+        store_id: &i32,
+        storage: &dyn FileStorage,
+        conn: &mut Connection
+    ) -> Result<Vec<ItemImage>, ServiceError> {
+        use crate::schema::product_item_image::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let product_item = Self::find_by_product(id, product_id, store_id, false, conn)?;
+
+        // every file is checked before any of them gets stored
+        let processed_images = files
+            .into_iter()
+            .map(process_image)
+            .collect::<Result<Vec<ProcessedImage>, ServiceError>>()?;
+
+        let mut images: Vec<Image> = Vec::with_capacity(processed_images.len());
+
+        for processed in processed_images {
+            match ImageService::create(processed, store_id, storage, conn) {
+                Ok(image) => images.push(image),
+                Err(e) => {
+                    // the images created so far have rows too, not just files
+                    ImageService::delete_images(&images, storage, conn);
+                    return Err(e);
+                }
+            }
+        }
+
+        let link_result = conn.transaction(|conn| {
+            let mut image_ids = Self::lock_images(&product_item.id, conn)?;
+
+            if image_ids.len() + images.len() > MAX_ITEM_IMAGES {
+                return Err(ServiceError::BadRequest {
+                    error_message: format!("An item can have at most {} images", MAX_ITEM_IMAGES),
+                });
+            }
+
+            let new_links: Vec<InsertableItemImage> = images
+                .iter()
+                .enumerate()
+                .map(|(index, image)| InsertableItemImage {
+                    product_item_id: product_item.id,
+                    image_id: image.id,
+                    position: (image_ids.len() + index) as i32,
+                })
+                .collect();
+
+            diesel::insert_into(dsl::product_item_image).values(new_links).execute(conn)?;
+
+            image_ids.extend(images.iter().map(|image| image.id));
+
+            Self::set_image_order(&product_item.id, &image_ids, conn)
+        });
+
+        if let Err(e) = link_result {
+            ImageService::delete_images(&images, storage, conn);
+            return Err(e);
+        }
+
+        Ok(
+            ProductService::item_images(&[product_item.id], conn)?
+                .remove(&product_item.id)
+                .unwrap_or_default()
+        )
+    }
+
+    pub fn reorder_images(
+        id: &i32,
+        product_id: &i32,
+        payload: ReorderImagesBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        validate(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let product_item = Self::find_by_product(id, product_id, store_id, false, conn)?;
+
+        conn.transaction(|conn| {
+            let mut image_ids = Self::lock_images(&product_item.id, conn)?;
+            let mut requested = payload.image_ids.clone();
+
+            image_ids.sort_unstable();
+            requested.sort_unstable();
+
+            if image_ids != requested {
+                return Err(ServiceError::BadRequest {
+                    error_message: "image_ids must list every image of the item once".to_string(),
+                });
+            }
+
+            Self::set_image_order(&product_item.id, &payload.image_ids, conn)
+        })
+    }
+
+    pub fn delete_image(
+        id: &i32,
+        product_id: &i32,
+        image_id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        storage: &dyn FileStorage,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::image::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageCatalog, conn)?;
+
+        let product_item = Self::find_by_product(id, product_id, store_id, false, conn)?;
+
+        let image = conn.transaction(|conn| {
+            let mut image_ids = Self::lock_images(&product_item.id, conn)?;
+
+            if !image_ids.contains(image_id) {
+                return Err(ServiceError::NotFound { error_message: "Image not found".to_string() });
+            }
+
+            let image = diesel
+                ::delete(dsl::image.find(image_id))
+                .returning(Image::as_returning())
+                .get_result(conn)?;
+
+            image_ids.retain(|other_id| other_id != image_id);
+
+            Self::set_image_order(&product_item.id, &image_ids, conn)?;
+
+            Ok(image)
+        })?;
+
+        ImageService::delete_files(&[image], storage);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,169 @@
+use bigdecimal::{ BigDecimal, FromPrimitive };
+use diesel::{
+    prelude::*,
+    pg::Pg,
+    query_builder::{ BoxedSqlQuery, SqlQuery },
+    sql_query,
+    sql_types::{ BigInt, Bool, Integer, Nullable, Numeric, Text },
+};
+use crate::{
+    controllers::product::ProductSearchQuery,
+    models::{
+        product::{
+            MatchedProduct,
+            CategoryFacet,
+            VariantFacet,
+            SearchFacets,
+            ProductSearchResponse,
+        },
+        response::PaginatedResponse,
+    },
+    error::ServiceError,
+    utils::validation::validate,
+};
+use super::{ Connection, product::ProductService };
+
+/// Every product item matching the search, one row per item. Prices are compared
+/// after applying active discounts and stock after subtracting active reservations.
+const MATCHES_CTE: &str =
+    "WITH RECURSIVE search AS (
+        SELECT plainto_tsquery('simple', $2) AS query
+    ),
+    category_subtree AS (
+        SELECT id FROM public.product_category WHERE id = $3
+        UNION
+        SELECT c.id FROM public.product_category c
+        INNER JOIN category_subtree s ON c.parent_id = s.id
+    ),
+    matches AS (
+        SELECT
+        p.id AS product_id,
+        p.category_id,
+        i.id AS product_item_id,
+        ts_rank(
+            to_tsvector('simple', p.name) || to_tsvector('simple', coalesce(i.description, '')),
+            search.query
+        ) AS rank
+        FROM public.product p
+        CROSS JOIN search
+        INNER JOIN public.product_item i ON i.product_id = p.id AND i.deleted = false
+        LEFT JOIN public.product_discount d ON d.id = i.discount_id AND d.expires_at > now()
+        WHERE p.store_id = $1
+        AND p.deleted = false
+        AND (
+            to_tsvector('simple', p.name) @@ search.query
+            OR to_tsvector('simple', coalesce(i.description, '')) @@ search.query
+        )
+        AND ($3 IS NULL OR p.category_id IN (SELECT id FROM category_subtree))
+        AND ($4 IS NULL OR COALESCE(round(i.price * (100 - d.percentual) / 100, 2), i.price) >= $4)
+        AND ($5 IS NULL OR COALESCE(round(i.price * (100 - d.percentual) / 100, 2), i.price) <= $5)
+        AND (
+            ($6 IS NULL AND $7 IS NULL)
+            OR EXISTS (
+                SELECT 1 FROM public.product_item_variant iv
+                INNER JOIN public.product_variant v ON v.id = iv.variant_id
+                INNER JOIN public.product_option o ON o.id = iv.option_id
+                WHERE iv.product_item_id = i.id
+                AND ($6 IS NULL OR lower(o.name) = lower($6))
+                AND ($7 IS NULL OR lower(v.value) = lower($7))
+            )
+        )
+        AND (
+            $8 = false
+            OR i.stock - COALESCE(
+                (
+                    SELECT SUM(r.quantity) FROM public.stock_reservation r
+                    WHERE r.product_item_id = i.id AND r.expires_at > now()
+                ),
+                0
+            ) > 0
+        )
+    )";
+
+#[derive(QueryableByName)]
+struct MatchCount {
+    #[diesel(sql_type = BigInt)]
+    total: i64,
+}
+
+pub struct ProductSearchService;
+
+impl ProductSearchService {
+    fn matches_query<'a>(
+        select: &str,
+        store_id: &i32,
+        query: &'a ProductSearchQuery
+    ) -> BoxedSqlQuery<'a, Pg, SqlQuery> {
+        sql_query(format!("{} {}", MATCHES_CTE, select))
+            .into_boxed()
+            .bind::<Integer, _>(store_id.to_owned())
+            .bind::<Text, _>(&query.q)
+            .bind::<Nullable<Integer>, _>(query.category_id)
+            .bind::<Nullable<Numeric>, _>(query.min_price.and_then(BigDecimal::from_f64))
+            .bind::<Nullable<Numeric>, _>(query.max_price.and_then(BigDecimal::from_f64))
+            .bind::<Nullable<Text>, _>(&query.variant_name)
+            .bind::<Nullable<Text>, _>(&query.variant_value)
+            .bind::<Bool, _>(query.in_stock.unwrap_or(false))
+    }
+
+    pub fn search(
+        store_id: &i32,
+        query: ProductSearchQuery,
+        conn: &mut Connection
+    ) -> Result<ProductSearchResponse, ServiceError> {
+        validate(&query)?;
+
+        let page = query.page.unwrap_or(1);
+        let per_page = query.per_page.unwrap_or(20);
+
+        let total = Self::matches_query(
+            "SELECT COUNT(DISTINCT product_id) AS total FROM matches",
+            store_id,
+            &query
+        ).get_result::<MatchCount>(conn)?.total;
+
+        let product_ids: Vec<i32> = Self::matches_query(
+            "SELECT product_id FROM matches
+            GROUP BY product_id
+            ORDER BY MAX(rank) DESC, product_id ASC
+            LIMIT $9 OFFSET $10",
+            store_id,
+            &query
+        )
+            .bind::<BigInt, _>(per_page)
+            .bind::<BigInt, _>((page - 1) * per_page)
+            .get_results::<MatchedProduct>(conn)?
+            .into_iter()
+            .map(|matched| matched.product_id)
+            .collect();
+
+        let categories = Self::matches_query(
+            "SELECT c.id, c.name, COUNT(DISTINCT m.product_id)::INTEGER AS count
+            FROM matches m
+            INNER JOIN public.product_category c ON c.id = m.category_id
+            GROUP BY c.id, c.name
+            ORDER BY count DESC, c.name ASC",
+            store_id,
+            &query
+        ).get_results::<CategoryFacet>(conn)?;
+
+        let variants = Self::matches_query(
+            "SELECT o.name, v.value, COUNT(DISTINCT m.product_id)::INTEGER AS count
+            FROM matches m
+            INNER JOIN public.product_item_variant iv ON iv.product_item_id = m.product_item_id
+            INNER JOIN public.product_variant v ON v.id = iv.variant_id
+            INNER JOIN public.product_option o ON o.id = iv.option_id
+            GROUP BY o.name, v.value
+            ORDER BY o.name ASC, count DESC",
+            store_id,
+            &query
+        ).get_results::<VariantFacet>(conn)?;
+
+        let items = ProductService::catalog_details(&product_ids, conn)?;
+
+        Ok(ProductSearchResponse {
+            results: PaginatedResponse { items, total, page, per_page },
+            facets: SearchFacets { categories, variants },
+        })
+    }
+}
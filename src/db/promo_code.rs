@@ -0,0 +1,563 @@
+use bigdecimal::{ BigDecimal, FromPrimitive, Zero };
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{ Integer, Text },
+    result::{ DatabaseErrorKind, Error as DieselError },
+};
+use crate::{
+    controllers::promo_code::{ PromoCodeBody, ApplyPromoCodeBody },
+    models::{
+        promo_code::{
+            PromoCode,
+            InsertablePromoCode,
+            InsertablePromoCodeRedemption,
+            DiscountType,
+            PricedLine,
+            AppliedPromoCode,
+        },
+        response::IDResponse,
+        role::Permission,
+    },
+    error::ServiceError,
+    utils::validation::validate,
+};
+use super::{
+    Connection,
+    store::StoreService,
+    shopping_cart::ShoppingCartService,
+    product::ProductService,
+};
+
+#[derive(QueryableByName)]
+struct UsageCount {
+    #[diesel(sql_type = Integer)]
+    total_uses: i32,
+    #[diesel(sql_type = Integer)]
+    customer_uses: i32,
+}
+
+pub struct PromoCodeService;
+
+impl PromoCodeService {
+    fn check_payload(payload: &PromoCodeBody) -> Result<(), ServiceError> {
+        validate(payload)?;
+
+        if payload.discount_type == DiscountType::Percentual && payload.amount > 100.0 {
+            return Err(ServiceError::BadRequest {
+                error_message: "Percentual discount can't be greater than 100".to_string(),
+            });
+        }
+
+        if let (Some(starts_at), Some(expires_at)) = (payload.starts_at, payload.expires_at) {
+            if starts_at >= expires_at {
+                return Err(ServiceError::BadRequest {
+                    error_message: "Promo code must start before it expires".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn map_write_error(e: DieselError) -> ServiceError {
+        match e {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) =>
+                ServiceError::BadRequest {
+                    error_message: "Promo code already exists".to_string(),
+                },
+            _ => ServiceError::InternalServerError { error_message: e.to_string() },
+        }
+    }
+
+    pub fn find(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<PromoCode, ServiceError> {
+        let promo_code_result = sql_query(
+            "SELECT * FROM public.promo_code WHERE id = $1 AND store_id = $2 AND deleted = false"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<PromoCode>(conn);
+
+        match promo_code_result {
+            Ok(promo_code) => Ok(promo_code),
+            Err(_) =>
+                Err(ServiceError::NotFound { error_message: "Promo code not found".to_string() }),
+        }
+    }
+
+    pub fn create(
+        payload: PromoCodeBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        use crate::schema::promo_code;
+
+        Self::check_payload(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let new_promo_code = InsertablePromoCode {
+            code: payload.code.to_uppercase(),
+            discount_type: payload.discount_type as i16,
+            amount: BigDecimal::from_f64(payload.amount).unwrap().round(2),
+            min_cart_total: payload.min_cart_total.and_then(BigDecimal::from_f64),
+            max_uses: payload.max_uses,
+            max_uses_per_customer: payload.max_uses_per_customer,
+            category_ids: payload.category_ids,
+            product_ids: payload.product_ids,
+            starts_at: payload.starts_at,
+            expires_at: payload.expires_at,
+            inactive: payload.inactive,
+            store_id: store_id.to_owned(),
+        };
+
+        match
+            diesel
+                ::insert_into(promo_code::dsl::promo_code)
+                .values(new_promo_code)
+                .returning(promo_code::dsl::id)
+                .get_result::<i32>(conn)
+        {
+            Ok(id) => Ok(IDResponse { id }),
+            Err(e) => Err(Self::map_write_error(e)),
+        }
+    }
+
+    pub fn list(
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<PromoCode>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let promo_codes_result = sql_query(
+            "SELECT * FROM public.promo_code WHERE store_id = $1 AND deleted = false ORDER BY created_at DESC"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<PromoCode>(conn);
+
+        match promo_codes_result {
+            Ok(promo_codes) => Ok(promo_codes),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn edit(
+        id: &i32,
+        payload: PromoCodeBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::promo_code::dsl;
+
+        Self::check_payload(&payload)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let existing_promo_code = Self::find(id, store_id, conn)?;
+
+        let update_result = diesel
+            ::update(dsl::promo_code)
+            .filter(dsl::id.eq(existing_promo_code.id))
+            .set((
+                dsl::code.eq(payload.code.to_uppercase()),
+                dsl::discount_type.eq(payload.discount_type as i16),
+                dsl::amount.eq(BigDecimal::from_f64(payload.amount).unwrap().round(2)),
+                dsl::min_cart_total.eq(payload.min_cart_total.and_then(BigDecimal::from_f64)),
+                dsl::max_uses.eq(payload.max_uses),
+                dsl::max_uses_per_customer.eq(payload.max_uses_per_customer),
+                dsl::category_ids.eq(payload.category_ids),
+                dsl::product_ids.eq(payload.product_ids),
+                dsl::starts_at.eq(payload.starts_at),
+                dsl::expires_at.eq(payload.expires_at),
+                dsl::inactive.eq(payload.inactive),
+                dsl::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Self::map_write_error(e)),
+        }
+    }
+
+    pub fn delete(
+        id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::promo_code::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManagePromotions, conn)?;
+
+        let existing_promo_code = Self::find(id, store_id, conn)?;
+
+        let delete_result = diesel
+            ::update(dsl::promo_code)
+            .filter(dsl::id.eq(existing_promo_code.id))
+            .set((dsl::deleted.eq(true), dsl::updated_at.eq(diesel::dsl::now)))
+            .execute(conn);
+
+        match delete_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    fn find_usable_by_code(
+        code: &str,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<PromoCode, ServiceError> {
+        let promo_code_result = sql_query(
+            "SELECT * FROM public.promo_code
+            WHERE code = $1
+            AND store_id = $2
+            AND inactive = false
+            AND deleted = false
+            AND (starts_at IS NULL OR starts_at <= now())
+            AND (expires_at IS NULL OR expires_at > now())"
+        )
+            .bind::<Text, _>(code.to_uppercase())
+            .bind::<Integer, _>(store_id)
+            .get_result::<PromoCode>(conn);
+
+        match promo_code_result {
+            Ok(promo_code) => Ok(promo_code),
+            Err(_) =>
+                Err(ServiceError::BadRequest {
+                    error_message: "Promo code is not valid".to_string(),
+                }),
+        }
+    }
+
+    fn lock_usable(id: &i32, conn: &mut Connection) -> Result<PromoCode, ServiceError> {
+        let promo_code_result = sql_query(
+            "SELECT * FROM public.promo_code
+            WHERE id = $1
+            AND inactive = false
+            AND deleted = false
+            AND (starts_at IS NULL OR starts_at <= now())
+            AND (expires_at IS NULL OR expires_at > now())
+            FOR UPDATE"
+        )
+            .bind::<Integer, _>(id)
+            .get_result::<PromoCode>(conn);
+
+        match promo_code_result {
+            Ok(promo_code) => Ok(promo_code),
+            Err(_) =>
+                Err(ServiceError::BadRequest {
+                    error_message: "Promo code is no longer valid".to_string(),
+                }),
+        }
+    }
+
+    fn check_usage(
+        promo_code: &PromoCode,
+        customer_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let usage = sql_query(
+            "SELECT
+            COUNT(*)::INTEGER AS total_uses,
+            COUNT(*) FILTER (WHERE customer_id = $2)::INTEGER AS customer_uses
+            FROM public.promo_code_redemption
+            WHERE promo_code_id = $1"
+        )
+            .bind::<Integer, _>(promo_code.id)
+            .bind::<Integer, _>(customer_id)
+            .get_result::<UsageCount>(conn)?;
+
+        let exhausted =
+            promo_code.max_uses.is_some_and(|max_uses| usage.total_uses >= max_uses) ||
+            promo_code.max_uses_per_customer.is_some_and(|max_uses| usage.customer_uses >= max_uses);
+
+        if exhausted {
+            return Err(ServiceError::BadRequest {
+                error_message: "Promo code usage limit reached".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn priced_lines(
+        shopping_cart_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<PricedLine>, ServiceError> {
+        let lines_result = sql_query(
+            "SELECT
+            p.id AS product_id,
+            p.category_id,
+            o.unit_price * o.quantity AS subtotal
+            FROM public.order_item o
+            INNER JOIN public.product_item i ON i.id = o.product_item_id
+            INNER JOIN public.product p ON p.id = i.product_id
+            WHERE o.shopping_cart_id = $1"
+        )
+            .bind::<Integer, _>(shopping_cart_id)
+            .get_results::<PricedLine>(conn);
+
+        match lines_result {
+            Ok(lines) => Ok(lines),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    /// Categories the promo code is restricted to, including their subcategories.
+    fn eligible_category_ids(
+        promo_code: &PromoCode,
+        conn: &mut Connection
+    ) -> Result<Vec<i32>, ServiceError> {
+        if promo_code.category_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        ProductService::category_subtree_ids(&promo_code.category_ids, conn)
+    }
+
+    /// Discount granted by `promo_code` on the given lines. Only lines matching the
+    /// category/product restrictions count, while the minimum applies to the whole cart.
+    /// `category_ids` are the code's categories expanded to their subcategories.
+    fn discount_for(
+        promo_code: &PromoCode,
+        category_ids: &[i32],
+        lines: &[PricedLine]
+    ) -> Result<BigDecimal, ServiceError> {
+        let cart_total = lines
+            .iter()
+            .fold(BigDecimal::zero(), |total, line| total + &line.subtotal);
+
+        if let Some(min_cart_total) = &promo_code.min_cart_total {
+            if &cart_total < min_cart_total {
+                return Err(ServiceError::BadRequest {
+                    error_message: format!("Promo code requires a minimum cart total of {}", min_cart_total),
+                });
+            }
+        }
+
+        let unrestricted = promo_code.category_ids.is_empty() && promo_code.product_ids.is_empty();
+
+        let eligible_total = lines
+            .iter()
+            .filter(|line| {
+                unrestricted ||
+                    category_ids.contains(&line.category_id) ||
+                    promo_code.product_ids.contains(&line.product_id)
+            })
+            .fold(BigDecimal::zero(), |total, line| total + &line.subtotal);
+
+        if eligible_total.is_zero() {
+            return Err(ServiceError::BadRequest {
+                error_message: "Promo code doesn't apply to any product in the cart".to_string(),
+            });
+        }
+
+        let discount = match DiscountType::from_i16(promo_code.discount_type) {
+            DiscountType::Percentual =>
+                (&eligible_total * &promo_code.amount) / BigDecimal::from(100),
+            DiscountType::Fixed => promo_code.amount.clone().min(eligible_total),
+        };
+
+        Ok(discount.round(2))
+    }
+
+    pub fn apply_to_cart(
+        shopping_cart_id: &i32,
+        payload: ApplyPromoCodeBody,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<AppliedPromoCode, ServiceError> {
+        use crate::schema::shopping_cart::dsl;
+
+        validate(&payload)?;
+
+        let shopping_cart = ShoppingCartService::find_owned(shopping_cart_id, user_id, conn)?;
+
+        let promo_code = Self::find_usable_by_code(&payload.code, &shopping_cart.store_id, conn)?;
+
+        Self::check_usage(&promo_code, user_id, conn)?;
+
+        let category_ids = Self::eligible_category_ids(&promo_code, conn)?;
+
+        let discount = Self::discount_for(
+            &promo_code,
+            &category_ids,
+            &Self::priced_lines(shopping_cart_id, conn)?
+        )?;
+
+        diesel
+            ::update(dsl::shopping_cart)
+            .filter(dsl::id.eq(shopping_cart.id))
+            .set(dsl::promo_code_id.eq(promo_code.id))
+            .execute(conn)?;
+
+        Ok(AppliedPromoCode { code: promo_code.code, discount })
+    }
+
+    pub fn remove_from_cart(
+        shopping_cart_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::shopping_cart::dsl;
+
+        let shopping_cart = ShoppingCartService::find_owned(shopping_cart_id, user_id, conn)?;
+
+        diesel
+            ::update(dsl::shopping_cart)
+            .filter(dsl::id.eq(shopping_cart.id))
+            .set(dsl::promo_code_id.eq(None::<i32>))
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Meant to run inside the checkout transaction: the promo code row stays locked
+    /// until commit so concurrent checkouts can't exceed the usage limits.
+    pub fn checkout_discount(
+        promo_code_id: &i32,
+        shopping_cart_id: &i32,
+        store_id: &i32,
+        customer_id: &i32,
+        conn: &mut Connection
+    ) -> Result<BigDecimal, ServiceError> {
+        let promo_code = Self::lock_usable(promo_code_id, conn)?;
+
+        if promo_code.store_id != *store_id {
+            return Err(ServiceError::BadRequest {
+                error_message: "Promo code is no longer valid".to_string(),
+            });
+        }
+
+        Self::check_usage(&promo_code, customer_id, conn)?;
+
+        let category_ids = Self::eligible_category_ids(&promo_code, conn)?;
+
+        Self::discount_for(&promo_code, &category_ids, &Self::priced_lines(shopping_cart_id, conn)?)
+    }
+
+    pub fn redeem(
+        promo_code_id: &i32,
+        customer_id: &i32,
+        order_id: &i32,
+        discount: BigDecimal,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::promo_code_redemption;
+
+        let redemption_result = diesel
+            ::insert_into(promo_code_redemption::dsl::promo_code_redemption)
+            .values(InsertablePromoCodeRedemption {
+                discount,
+                promo_code_id: promo_code_id.to_owned(),
+                customer_id: customer_id.to_owned(),
+                order_id: order_id.to_owned(),
+            })
+            .execute(conn);
+
+        match redemption_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn promo_code(discount_type: DiscountType, amount: &str) -> PromoCode {
+        PromoCode {
+            id: 1,
+            code: "WELCOME".to_string(),
+            discount_type: discount_type as i16,
+            amount: BigDecimal::from_str(amount).unwrap(),
+            min_cart_total: None,
+            max_uses: None,
+            max_uses_per_customer: None,
+            category_ids: Vec::new(),
+            product_ids: Vec::new(),
+            starts_at: None,
+            expires_at: None,
+            inactive: false,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            store_id: 1,
+        }
+    }
+
+    fn line(product_id: i32, category_id: i32, subtotal: &str) -> PricedLine {
+        PricedLine {
+            product_id,
+            category_id,
+            subtotal: BigDecimal::from_str(subtotal).unwrap(),
+        }
+    }
+
+    fn decimal(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn percentual_discount_applies_to_the_whole_cart() {
+        let lines = [line(1, 1, "40.00"), line(2, 2, "9.99")];
+
+        let discount = PromoCodeService::discount_for(&promo_code(DiscountType::Percentual, "10"), &[], &lines);
+
+        assert_eq!(discount.unwrap(), decimal("5.00"));
+    }
+
+    #[test]
+    fn fixed_discount_is_capped_at_the_eligible_total() {
+        let mut code = promo_code(DiscountType::Fixed, "50.00");
+        code.product_ids = vec![2];
+
+        let lines = [line(1, 1, "100.00"), line(2, 1, "30.00")];
+
+        assert_eq!(PromoCodeService::discount_for(&code, &[], &lines).unwrap(), decimal("30.00"));
+    }
+
+    #[test]
+    fn fixed_discount_below_the_total_is_applied_in_full() {
+        let lines = [line(1, 1, "100.00")];
+
+        let discount = PromoCodeService::discount_for(&promo_code(DiscountType::Fixed, "15.50"), &[], &lines);
+
+        assert_eq!(discount.unwrap(), decimal("15.50"));
+    }
+
+    #[test]
+    fn minimum_cart_total_is_inclusive() {
+        let mut code = promo_code(DiscountType::Fixed, "5.00");
+        code.min_cart_total = Some(decimal("50.00"));
+
+        assert!(PromoCodeService::discount_for(&code, &[], &[line(1, 1, "50.00")]).is_ok());
+        assert!(PromoCodeService::discount_for(&code, &[], &[line(1, 1, "49.99")]).is_err());
+    }
+
+    #[test]
+    fn restricted_code_without_matching_lines_is_rejected() {
+        let mut code = promo_code(DiscountType::Percentual, "10");
+        code.category_ids = vec![7];
+
+        // 8 is a subcategory of 7
+        let category_ids = [7, 8];
+
+        assert!(PromoCodeService::discount_for(&code, &category_ids, &[line(1, 1, "20.00")]).is_err());
+        assert!(PromoCodeService::discount_for(&code, &category_ids, &[line(1, 7, "20.00")]).is_ok());
+        assert!(PromoCodeService::discount_for(&code, &category_ids, &[line(1, 8, "20.00")]).is_ok());
+    }
+}
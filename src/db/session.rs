@@ -0,0 +1,139 @@
+use diesel::{
+    prelude::*,
+    dsl::{ now, IntervalDsl },
+    pg::data_types::PgInterval,
+    sql_query,
+    sql_types::{ Integer, Text },
+};
+use crate::{
+    models::session::UserSession,
+    error::ServiceError,
+    utils::token::{ generate_token, hash_token },
+};
+use super::Connection;
+
+const DEFAULT_REFRESH_TOKEN_DAYS: i32 = 30;
+
+pub struct SessionService;
+
+impl SessionService {
+    fn refresh_token_lifetime() -> PgInterval {
+        std::env::var("REFRESH_TOKEN_DAYS")
+            .ok()
+            .and_then(|days| days.parse().ok())
+            .unwrap_or(DEFAULT_REFRESH_TOKEN_DAYS)
+            .days()
+    }
+
+    /// Opens a new session for `user_id` and returns it along with the plain refresh token.
+    pub fn create(user_id: &i32, conn: &mut Connection) -> Result<(i32, String), ServiceError> {
+        use crate::schema::user_session;
+
+        let refresh_token = generate_token();
+
+        let session_result = diesel
+            ::insert_into(user_session::dsl::user_session)
+            .values((
+                user_session::dsl::refresh_token_hash.eq(hash_token(&refresh_token)),
+                user_session::dsl::expires_at.eq(now + Self::refresh_token_lifetime()),
+                user_session::dsl::user_id.eq(user_id),
+            ))
+            .returning(user_session::dsl::id)
+            .get_result::<i32>(conn);
+
+        match session_result {
+            Ok(session_id) => Ok((session_id, refresh_token)),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    /// Swaps the refresh token of the session it belongs to for a new one. Presenting a
+    /// token that was already rotated means it leaked, so that session gets revoked and
+    /// `None` is returned; callers must let that revocation commit.
+    pub fn rotate(
+        refresh_token: &str,
+        conn: &mut Connection
+    ) -> Result<Option<(UserSession, String)>, ServiceError> {
+        use crate::schema::user_session::dsl;
+
+        let token_hash = hash_token(refresh_token);
+
+        let session = sql_query(
+            "SELECT id, user_id FROM public.user_session
+            WHERE refresh_token_hash = $1
+            AND revoked_at IS NULL
+            AND expires_at > now()
+            FOR UPDATE"
+        )
+            .bind::<Text, _>(&token_hash)
+            .get_result::<UserSession>(conn)
+            .optional()?;
+
+        let session = match session {
+            Some(session) => session,
+            None => {
+                sql_query(
+                    "UPDATE public.user_session SET revoked_at = now(), updated_at = now()
+                    WHERE previous_token_hash = $1 AND revoked_at IS NULL"
+                )
+                    .bind::<Text, _>(&token_hash)
+                    .execute(conn)?;
+
+                return Ok(None);
+            }
+        };
+
+        let new_refresh_token = generate_token();
+
+        diesel
+            ::update(dsl::user_session)
+            .filter(dsl::id.eq(session.id))
+            .set((
+                dsl::previous_token_hash.eq(Some(token_hash)),
+                dsl::refresh_token_hash.eq(hash_token(&new_refresh_token)),
+                dsl::expires_at.eq(now + Self::refresh_token_lifetime()),
+                dsl::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+
+        Ok(Some((session, new_refresh_token)))
+    }
+
+    pub fn revoke(session_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+        let revoke_result = sql_query(
+            "UPDATE public.user_session SET revoked_at = now(), updated_at = now()
+            WHERE id = $1 AND revoked_at IS NULL"
+        )
+            .bind::<Integer, _>(session_id)
+            .execute(conn);
+
+        match revoke_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn revoke_all(user_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+        sql_query(
+            "UPDATE public.user_session SET revoked_at = now(), updated_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL"
+        )
+            .bind::<Integer, _>(user_id)
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    pub fn is_active(session_id: &i32, conn: &mut Connection) -> Result<bool, ServiceError> {
+        use crate::schema::user_session::dsl;
+
+        let count = dsl::user_session
+            .filter(dsl::id.eq(session_id))
+            .filter(dsl::revoked_at.is_null())
+            .filter(dsl::expires_at.gt(now))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,550 @@
+use bigdecimal::{ BigDecimal, FromPrimitive, Zero };
+use crate::{
+    controllers::shipping::{ ShippingMethodBody, ShippingRateBody },
+    models::{
+        address::UserAddress,
+        shipping::{
+            ShippingMethod,
+            ShippingMethodDetail,
+            ShippingRate,
+            ShippingRateType,
+            InsertableShippingMethod,
+            InsertableShippingRate,
+            InsertableShippingInformation,
+        },
+        order::ShippingStatus,
+        response::IDResponse,
+        role::Permission,
+    },
+    error::ServiceError,
+    utils::validation::validate,
+};
+use super::{ Connection, store::StoreService };
+use diesel::sql_types::{ Array, Integer, Text };
+use diesel::{ prelude::*, sql_query, Connection as _ };
+
+pub struct ShippingService;
+
+impl ShippingService {
+    pub fn find_active_method(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ShippingMethod, ServiceError> {
+        let shipping_method_result = sql_query(
+            "SELECT * FROM public.shipping_method 
+            WHERE id = $1 
+            AND store_id = $2 
+            AND inactive = false 
+            AND deleted = false"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<ShippingMethod>(conn);
+
+        match shipping_method_result {
+            Ok(shipping_method) => Ok(shipping_method),
+            Err(_) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Shipping method not found".to_string(),
+                }),
+        }
+    }
+
+    pub fn find(
+        id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ShippingMethod, ServiceError> {
+        let shipping_method_result = sql_query(
+            "SELECT * FROM public.shipping_method WHERE id = $1 AND store_id = $2 AND deleted = false"
+        )
+            .bind::<Integer, _>(id)
+            .bind::<Integer, _>(store_id)
+            .get_result::<ShippingMethod>(conn);
+
+        match shipping_method_result {
+            Ok(shipping_method) => Ok(shipping_method),
+            Err(_) =>
+                Err(ServiceError::NotFound {
+                    error_message: "Shipping method not found".to_string(),
+                }),
+        }
+    }
+
+    pub fn list_rates(
+        shipping_method_ids: &[i32],
+        conn: &mut Connection
+    ) -> Result<Vec<ShippingRate>, ServiceError> {
+        let rates_result = sql_query(
+            "SELECT * FROM public.shipping_rate
+            WHERE shipping_method_id = ANY($1)
+            ORDER BY country NULLS LAST, rate_type, min_value NULLS FIRST, id"
+        )
+            .bind::<Array<Integer>, _>(shipping_method_ids)
+            .get_results::<ShippingRate>(conn);
+
+        match rates_result {
+            Ok(rates) => Ok(rates),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    fn with_rates(
+        shipping_methods: Vec<ShippingMethod>,
+        conn: &mut Connection
+    ) -> Result<Vec<ShippingMethodDetail>, ServiceError> {
+        let ids: Vec<i32> = shipping_methods
+            .iter()
+            .map(|method| method.id)
+            .collect();
+
+        let mut rates = Self::list_rates(&ids, conn)?;
+
+        Ok(
+            shipping_methods
+                .into_iter()
+                .map(|method| {
+                    let (method_rates, rest) = rates
+                        .drain(..)
+                        .partition(|rate| rate.shipping_method_id == method.id);
+
+                    rates = rest;
+
+                    ShippingMethodDetail { method, rates: method_rates }
+                })
+                .collect()
+        )
+    }
+
+    /// What customers can pick from at checkout, with the rates used to price them.
+    pub fn list_active(
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ShippingMethodDetail>, ServiceError> {
+        let shipping_methods = sql_query(
+            "SELECT * FROM public.shipping_method
+            WHERE store_id = $1 AND inactive = false AND deleted = false
+            ORDER BY name"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<ShippingMethod>(conn)?;
+
+        Self::with_rates(shipping_methods, conn)
+    }
+
+    pub fn list(
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<ShippingMethodDetail>, ServiceError> {
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let shipping_methods = sql_query(
+            "SELECT * FROM public.shipping_method WHERE store_id = $1 AND deleted = false ORDER BY name"
+        )
+            .bind::<Integer, _>(store_id)
+            .get_results::<ShippingMethod>(conn)?;
+
+        Self::with_rates(shipping_methods, conn)
+    }
+
+    fn to_decimal(value: Option<f64>) -> Option<BigDecimal> {
+        value.and_then(BigDecimal::from_f64).map(|value| value.round(2))
+    }
+
+    fn check_rates(rates: &[ShippingRateBody]) -> Result<(), ServiceError> {
+        for rate in rates {
+            let has_bounds = rate.min_value.is_some() || rate.max_value.is_some();
+
+            if rate.rate_type == ShippingRateType::Flat && has_bounds {
+                return Err(ServiceError::BadRequest {
+                    error_message: "Flat shipping rates can't have a min or max value".to_string(),
+                });
+            }
+
+            if let (Some(min_value), Some(max_value)) = (rate.min_value, rate.max_value) {
+                if min_value >= max_value {
+                    return Err(ServiceError::BadRequest {
+                        error_message: "Shipping rate min value must be lower than its max value".to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rates are always replaced as a whole, the admin UI edits them as a table.
+    fn replace_rates(
+        shipping_method_id: &i32,
+        rates: Vec<ShippingRateBody>,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::shipping_rate::dsl;
+
+        diesel
+            ::delete(dsl::shipping_rate)
+            .filter(dsl::shipping_method_id.eq(shipping_method_id))
+            .execute(conn)?;
+
+        let new_rates: Vec<InsertableShippingRate> = rates
+            .into_iter()
+            .map(|rate| InsertableShippingRate {
+                rate_type: rate.rate_type as i16,
+                country: rate.country
+                    .map(|country| country.trim().to_string())
+                    .filter(|country| !country.is_empty()),
+                min_value: Self::to_decimal(rate.min_value),
+                max_value: Self::to_decimal(rate.max_value),
+                price: BigDecimal::from_f64(rate.price).unwrap().round(2),
+                shipping_method_id: shipping_method_id.to_owned(),
+            })
+            .collect();
+
+        diesel::insert_into(dsl::shipping_rate).values(new_rates).execute(conn)?;
+
+        Ok(())
+    }
+
+    pub fn create(
+        payload: ShippingMethodBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        use crate::schema::shipping_method;
+
+        validate(&payload)?;
+
+        Self::check_rates(&payload.rates)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        conn.transaction(|conn| {
+            let id = diesel
+                ::insert_into(shipping_method::dsl::shipping_method)
+                .values((
+                    InsertableShippingMethod {
+                        name: payload.name,
+                        store_id: store_id.to_owned(),
+                    },
+                    shipping_method::dsl::inactive.eq(payload.inactive),
+                ))
+                .returning(shipping_method::dsl::id)
+                .get_result::<i32>(conn)?;
+
+            Self::replace_rates(&id, payload.rates, conn)?;
+
+            Ok(IDResponse { id })
+        })
+    }
+
+    pub fn edit(
+        id: &i32,
+        payload: ShippingMethodBody,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::shipping_method::dsl;
+
+        validate(&payload)?;
+
+        Self::check_rates(&payload.rates)?;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let existing_shipping_method = Self::find(id, store_id, conn)?;
+
+        conn.transaction(|conn| {
+            diesel
+                ::update(dsl::shipping_method)
+                .filter(dsl::id.eq(existing_shipping_method.id))
+                .set((
+                    dsl::name.eq(payload.name),
+                    dsl::inactive.eq(payload.inactive),
+                    dsl::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+
+            Self::replace_rates(&existing_shipping_method.id, payload.rates, conn)
+        })
+    }
+
+    /// Soft deletes, orders shipped with the method keep pointing at it.
+    pub fn delete(
+        id: &i32,
+        user_id: &i32,
+        store_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::shipping_method::dsl;
+
+        StoreService::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let existing_shipping_method = Self::find(id, store_id, conn)?;
+
+        let delete_result = diesel
+            ::update(dsl::shipping_method)
+            .filter(dsl::id.eq(existing_shipping_method.id))
+            .set((dsl::deleted.eq(true), dsl::updated_at.eq(diesel::dsl::now)))
+            .execute(conn);
+
+        match delete_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    fn rate_applies(rate: &ShippingRate, weight: &BigDecimal, order_total: &BigDecimal) -> bool {
+        let measure = match ShippingRateType::from_i16(rate.rate_type) {
+            ShippingRateType::Flat => {
+                return true;
+            }
+            ShippingRateType::Weight => weight,
+            ShippingRateType::Total => order_total,
+        };
+
+        rate.min_value.as_ref().is_none_or(|min_value| measure >= min_value) &&
+            rate.max_value.as_ref().is_none_or(|max_value| measure < max_value)
+    }
+
+    /// Cheapest of `rates` that applies to the destination `country`, weight (in grams) and
+    /// items total. Rates set up for the destination country take precedence over the ones
+    /// without a country.
+    fn cheapest_rate(
+        rates: &[ShippingRate],
+        country: &str,
+        weight_grams: i64,
+        order_total: &BigDecimal
+    ) -> Option<BigDecimal> {
+        let country_rates: Vec<&ShippingRate> = rates
+            .iter()
+            .filter(|rate| {
+                rate.country.as_ref().is_some_and(|rate_country| rate_country.eq_ignore_ascii_case(country))
+            })
+            .collect();
+
+        let candidates = if country_rates.is_empty() {
+            rates
+                .iter()
+                .filter(|rate| rate.country.is_none())
+                .collect()
+        } else {
+            country_rates
+        };
+
+        let weight = BigDecimal::from(weight_grams);
+
+        candidates
+            .into_iter()
+            .filter(|rate| Self::rate_applies(rate, &weight, order_total))
+            .map(|rate| rate.price.clone())
+            .min()
+    }
+
+    /// Price of shipping an order with the given weight (in grams) and items total to `country`.
+    /// Rates set up for the destination country take precedence over the ones without a
+    /// country, and the cheapest applicable rate wins. Methods without any rate ship for free.
+    pub fn quote(
+        shipping_method: &ShippingMethod,
+        country: &str,
+        weight_grams: i64,
+        order_total: &BigDecimal,
+        conn: &mut Connection
+    ) -> Result<BigDecimal, ServiceError> {
+        let rates = Self::list_rates(&[shipping_method.id], conn)?;
+
+        if rates.is_empty() {
+            return Ok(BigDecimal::zero());
+        }
+
+        let country = country.trim();
+
+        Self::cheapest_rate(&rates, country, weight_grams, order_total).ok_or(
+            ServiceError::BadRequest {
+                error_message: format!("{} doesn't ship this order to {}", shipping_method.name, country),
+            }
+        )
+    }
+
+    pub fn create_information(
+        address: &UserAddress,
+        shipping_method_id: &i32,
+        shipping_price: BigDecimal,
+        conn: &mut Connection
+    ) -> Result<i32, ServiceError> {
+        use crate::schema::shipping_information;
+
+        let new_shipping_information = InsertableShippingInformation {
+            status: ShippingStatus::Pending as i32,
+            shipping_price,
+            address_id: address.id,
+            shipping_method_id: shipping_method_id.to_owned(),
+            address_line1: address.address_line1.clone(),
+            address_line2: address.address_line2.clone(),
+            number: address.number.clone(),
+            city: address.city.clone(),
+            country: address.country.clone(),
+            postal_code: address.postal_code.clone(),
+            phone_country_code: address.phone_country_code.clone(),
+            phone_number: address.phone_number.clone(),
+        };
+
+        let insert_result = diesel
+            ::insert_into(shipping_information::dsl::shipping_information)
+            .values(new_shipping_information)
+            .returning(shipping_information::dsl::id)
+            .get_result::<i32>(conn);
+
+        match insert_result {
+            Ok(id) => Ok(id),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn update_status(
+        id: &i32,
+        status: ShippingStatus,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query(
+            "UPDATE public.shipping_information SET status = $1, updated_at = now() WHERE id = $2"
+        )
+            .bind::<Integer, _>(status as i32)
+            .bind::<Integer, _>(id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn set_tracking_number(
+        id: &i32,
+        tracking_number: &str,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let update_result = sql_query(
+            "UPDATE public.shipping_information SET tracking_number = $1, updated_at = now() WHERE id = $2"
+        )
+            .bind::<Text, _>(tracking_number)
+            .bind::<Integer, _>(id)
+            .execute(conn);
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn rate(
+        rate_type: ShippingRateType,
+        country: Option<&str>,
+        min_value: Option<&str>,
+        max_value: Option<&str>,
+        price: &str
+    ) -> ShippingRate {
+        ShippingRate {
+            id: 1,
+            rate_type: rate_type as i16,
+            country: country.map(str::to_string),
+            min_value: min_value.map(decimal),
+            max_value: max_value.map(decimal),
+            price: decimal(price),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            shipping_method_id: 1,
+        }
+    }
+
+    fn decimal(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn min_is_inclusive_and_max_is_exclusive() {
+        let weight_rate = rate(ShippingRateType::Weight, None, Some("1000"), Some("5000"), "10");
+        let total = decimal("0");
+
+        assert!(!ShippingService::rate_applies(&weight_rate, &decimal("999"), &total));
+        assert!(ShippingService::rate_applies(&weight_rate, &decimal("1000"), &total));
+        assert!(ShippingService::rate_applies(&weight_rate, &decimal("4999"), &total));
+        assert!(!ShippingService::rate_applies(&weight_rate, &decimal("5000"), &total));
+    }
+
+    #[test]
+    fn total_rates_compare_the_items_total() {
+        let free_over_100 = rate(ShippingRateType::Total, None, Some("100.00"), None, "0");
+        let weight = decimal("250000");
+
+        assert!(!ShippingService::rate_applies(&free_over_100, &weight, &decimal("99.99")));
+        assert!(ShippingService::rate_applies(&free_over_100, &weight, &decimal("100.00")));
+    }
+
+    #[test]
+    fn flat_rates_ignore_their_bounds() {
+        let flat = rate(ShippingRateType::Flat, None, Some("10"), Some("20"), "5");
+
+        assert!(ShippingService::rate_applies(&flat, &decimal("0"), &decimal("1000")));
+    }
+
+    #[test]
+    fn country_rates_take_precedence_over_generic_ones() {
+        let rates = [
+            rate(ShippingRateType::Flat, None, None, None, "5.00"),
+            rate(ShippingRateType::Flat, Some("Argentina"), None, None, "12.00"),
+        ];
+
+        let total = decimal("50");
+
+        assert_eq!(
+            ShippingService::cheapest_rate(&rates, "argentina", 500, &total),
+            Some(decimal("12.00"))
+        );
+        assert_eq!(
+            ShippingService::cheapest_rate(&rates, "Uruguay", 500, &total),
+            Some(decimal("5.00"))
+        );
+    }
+
+    #[test]
+    fn cheapest_applicable_rate_wins() {
+        let rates = [
+            rate(ShippingRateType::Flat, None, None, None, "15.00"),
+            rate(ShippingRateType::Total, None, Some("100"), None, "0.00"),
+            rate(ShippingRateType::Weight, None, None, Some("1000"), "7.50"),
+        ];
+
+        assert_eq!(
+            ShippingService::cheapest_rate(&rates, "Chile", 500, &decimal("20")),
+            Some(decimal("7.50"))
+        );
+        assert_eq!(
+            ShippingService::cheapest_rate(&rates, "Chile", 500, &decimal("100")),
+            Some(decimal("0.00"))
+        );
+        assert_eq!(
+            ShippingService::cheapest_rate(&rates, "Chile", 2000, &decimal("20")),
+            Some(decimal("15.00"))
+        );
+    }
+
+    #[test]
+    fn no_applicable_rate_means_no_quote() {
+        let rates = [rate(ShippingRateType::Weight, Some("Chile"), None, Some("1000"), "7.50")];
+
+        assert_eq!(ShippingService::cheapest_rate(&rates, "Chile", 1000, &decimal("20")), None);
+    }
+}
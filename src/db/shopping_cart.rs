@@ -1,17 +1,36 @@
-use diesel::{ prelude::*, sql_query, sql_types::Integer };
+use bigdecimal::{ BigDecimal, Zero };
+use diesel::{ prelude::*, sql_query, sql_types::Integer, Connection as _ };
 use crate::{
     models::{
         response::IDResponse,
         shopping_cart::{ ShoppingCart, InsertableShoppingCart },
+        order::{ OrderItem, InsertableOrder, OrderStatus },
         product::ProductItem,
-        order::{ InsertableOrderItem, OrderItem },
+        shipping::ShippingQuote,
     },
     error::ServiceError,
-    controllers::shopping_cart::{ NewShoppingCartBody, EditShoppingCartBody },
+    controllers::shopping_cart::{
+        NewShoppingCartBody,
+        EditShoppingCartBody,
+        CheckoutBody,
+        ShippingQuoteBody,
+    },
     utils::validation::validate,
 };
 
-use super::{ Connection, order_item::OrderItemService, product_item::ProductItemService };
+use super::{
+    Connection,
+    order_item::OrderItemService,
+    product_item::ProductItemService,
+    order::OrderService,
+    payment_method::PaymentMethodService,
+    shipping::ShippingService,
+    address::AddressService,
+    product_discount::ProductDiscountService,
+    stock_reservation::StockReservationService,
+    promo_code::PromoCodeService,
+    email_verification::EmailVerificationService,
+};
 
 pub struct ShoppingCartService;
 
@@ -30,6 +49,32 @@ impl ShoppingCartService {
         }
     }
 
+    pub fn find_owned(
+        id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ShoppingCart, ServiceError> {
+        let shopping_cart = Self::find(id, conn)?;
+
+        if shopping_cart.customer_id != *user_id {
+            return Err(ServiceError::Forbidden {
+                error_message: "Shopping cart belongs to another user".to_string(),
+            });
+        }
+
+        Ok(shopping_cart)
+    }
+
+    fn check_same_store(product_item: &ProductItem, store_id: &i32) -> Result<(), ServiceError> {
+        if product_item.store_id != *store_id {
+            return Err(ServiceError::BadRequest {
+                error_message: "Product item belongs to another store".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn create(
         payload: NewShoppingCartBody,
         user_id: i32,
@@ -37,6 +82,8 @@ impl ShoppingCartService {
     ) -> Result<IDResponse<i32>, ServiceError> {
         use crate::schema::shopping_cart;
 
+        validate(&payload)?;
+
         let existing_shopping_cart = sql_query(
             "SELECT * FROM public.shopping_cart WHERE customer_id = $1 AND store_id = $2"
         )
@@ -50,122 +97,107 @@ impl ShoppingCartService {
             });
         }
 
-        let product_item = ProductItemService::find(&payload.product_item_id, false, conn)?;
+        conn.transaction(|conn| {
+            let product_item = ProductItemService::find_for_update(&payload.product_item_id, conn)?;
 
-        if product_item.stock < payload.quantity {
-            return Err(ServiceError::Forbidden {
-                error_message: "Quantity not available".to_string(),
-            });
-        }
+            Self::check_same_store(&product_item, &payload.store_id)?;
 
-        let new_shopping_cart = InsertableShoppingCart {
-            customer_id: user_id,
-            store_id: payload.store_id,
-        };
-
-        let new_cart_result = diesel
-            ::insert_into(shopping_cart::dsl::shopping_cart)
-            .values(new_shopping_cart)
-            .returning(shopping_cart::dsl::id)
-            .get_result::<i32>(conn)
-            .unwrap();
-
-        OrderItemService::create(
-            InsertableOrderItem {
-                order_id: None,
-                product_item_id: product_item.id,
-                quantity: payload.quantity,
-                shopping_cart_id: Some(new_cart_result),
-                unit_price: product_item.price,
-            },
-            conn
-        )?;
+            StockReservationService::check_available(&product_item, None, &payload.quantity, conn)?;
+
+            let new_shopping_cart = InsertableShoppingCart {
+                customer_id: user_id,
+                store_id: payload.store_id,
+            };
+
+            let new_cart_result = diesel
+                ::insert_into(shopping_cart::dsl::shopping_cart)
+                .values(new_shopping_cart)
+                .returning(shopping_cart::dsl::id)
+                .get_result::<i32>(conn)?;
+
+            let new_order_item = OrderItemService::create(
+                &product_item,
+                &payload.quantity,
+                &new_cart_result,
+                conn
+            )?;
 
-        Ok(IDResponse { id: new_cart_result })
+            StockReservationService::reserve(
+                &product_item.id,
+                &new_order_item.id,
+                &payload.quantity,
+                conn
+            )?;
+
+            Ok(IDResponse { id: new_cart_result })
+        })
     }
 
     pub fn edit(
         payload: EditShoppingCartBody,
         shopping_cart_id: i32,
+        user_id: &i32,
         conn: &mut Connection
     ) -> Result<(), ServiceError> {
         validate(&payload)?;
 
-        Self::find(&shopping_cart_id, conn)?;
+        let shopping_cart = Self::find_owned(&shopping_cart_id, user_id, conn)?;
 
-        let result = sql_query(
-            "SELECT * FROM public.order_item o
-        INNER JOIN public.product_item p ON p.id = o.product_item_id
-        WHERE o.shopping_cart_id = $1
-        AND p.id = $2
-        "
-        )
-            .bind::<Integer, _>(shopping_cart_id)
-            .bind::<Integer, _>(payload.product_item_id)
-            .get_results::<(OrderItem, ProductItem)>(conn);
-
-        match result {
-            Ok(select_result) => {
-                if select_result.is_empty() {
-                    match
-                        sql_query("SELECT * FROM public.product_item WHERE id = $1")
-                            .bind::<Integer, _>(payload.product_item_id)
-                            .get_result::<ProductItem>(conn)
-                    {
-                        Ok(p_item) => {
-                            if payload.quantity > p_item.stock {
-                                return Err(ServiceError::Forbidden {
-                                    error_message: "Quantity not available".to_string(),
-                                });
-                            } else {
-                                OrderItemService::create(
-                                    InsertableOrderItem {
-                                        product_item_id: payload.product_item_id,
-                                        quantity: payload.quantity,
-                                        shopping_cart_id: Some(shopping_cart_id),
-                                        unit_price: p_item.price,
-                                        order_id: None,
-                                    },
-                                    conn
-                                )?;
-
-                                Ok(())
-                            }
-                        }
-                        Err(_) => {
-                            return Err(ServiceError::NotFound {
-                                error_message: "Product not found".to_string(),
-                            });
-                        }
-                    }
-                } else {
-                    let (existing_order_item, existing_product_item) = &select_result[0];
-
-                    if payload.quantity > existing_product_item.stock {
-                        return Err(ServiceError::Forbidden {
-                            error_message: "Quantity not available".to_string(),
-                        });
-                    } else {
-                        OrderItemService::update_quantity(
-                            &payload.quantity,
-                            &existing_order_item.id,
-                            conn
-                        )?;
-
-                        Ok(())
-                    }
+        conn.transaction(|conn| {
+            let product_item = ProductItemService::find_for_update(&payload.product_item_id, conn)?;
+
+            Self::check_same_store(&product_item, &shopping_cart.store_id)?;
+
+            let existing_order_item = sql_query(
+                "SELECT * FROM public.order_item WHERE shopping_cart_id = $1 AND product_item_id = $2"
+            )
+                .bind::<Integer, _>(shopping_cart_id)
+                .bind::<Integer, _>(payload.product_item_id)
+                .get_result::<OrderItem>(conn)
+                .optional()?;
+
+            let order_item_id = match existing_order_item {
+                Some(order_item) => {
+                    StockReservationService::check_available(
+                        &product_item,
+                        Some(order_item.id),
+                        &payload.quantity,
+                        conn
+                    )?;
+
+                    OrderItemService::update_quantity(&payload.quantity, &order_item.id, conn)?;
+
+                    order_item.id
                 }
-            }
-            Err(e) => {
-                return Err(ServiceError::InternalServerError { error_message: e.to_string() });
-            }
-        }
+                None => {
+                    StockReservationService::check_available(
+                        &product_item,
+                        None,
+                        &payload.quantity,
+                        conn
+                    )?;
+
+                    OrderItemService::create(
+                        &product_item,
+                        &payload.quantity,
+                        &shopping_cart_id,
+                        conn
+                    )?.id
+                }
+            };
+
+            StockReservationService::reserve(&product_item.id, &order_item_id, &payload.quantity, conn)
+        })
     }
 
-    pub fn delete(shopping_cart_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+    pub fn delete(
+        shopping_cart_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
         use crate::schema::shopping_cart;
 
-        Self::find(&shopping_cart_id, conn)?;
+        Self::find_owned(shopping_cart_id, user_id, conn)?;
 
         let delete_result = diesel
             ::delete(shopping_cart::dsl::shopping_cart)
@@ -177,4 +209,193 @@ impl ShoppingCartService {
             Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
         }
     }
+
+    /// Shipping price checkout would charge for the cart. Total based rates look at the
+    /// items after product discounts, promo codes don't change the shipping price.
+    pub fn quote_shipping(
+        payload: ShippingQuoteBody,
+        shopping_cart_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<ShippingQuote, ServiceError> {
+        let shopping_cart = Self::find_owned(shopping_cart_id, user_id, conn)?;
+
+        let shipping_method = ShippingService::find_active_method(
+            &payload.shipping_method_id,
+            &shopping_cart.store_id,
+            conn
+        )?;
+
+        let address = AddressService::find(&payload.user_address_id, user_id, false, conn)?;
+
+        let order_items = OrderItemService::find_by_shopping_cart(shopping_cart_id, conn)?;
+
+        if order_items.is_empty() {
+            return Err(ServiceError::BadRequest {
+                error_message: "Shopping cart is empty".to_string(),
+            });
+        }
+
+        let mut items_total = BigDecimal::zero();
+        let mut weight_grams: i64 = 0;
+
+        for order_item in &order_items {
+            let product_item = ProductItemService::find(&order_item.product_item_id, false, conn)?;
+
+            let unit_price = ProductDiscountService::effective_price(&product_item, conn)?;
+
+            items_total += unit_price * BigDecimal::from(order_item.quantity);
+            weight_grams += i64::from(product_item.weight_grams.unwrap_or(0)) * i64::from(order_item.quantity);
+        }
+
+        let shipping_price = ShippingService::quote(
+            &shipping_method,
+            &address.country,
+            weight_grams,
+            &items_total,
+            conn
+        )?;
+
+        Ok(ShippingQuote { shipping_method_id: shipping_method.id, shipping_price })
+    }
+
+    pub fn checkout(
+        payload: CheckoutBody,
+        shopping_cart_id: i32,
+        user_id: i32,
+        conn: &mut Connection
+    ) -> Result<IDResponse<i32>, ServiceError> {
+        EmailVerificationService::check_verified(&user_id, conn)?;
+
+        conn.transaction(|conn| {
+            let shopping_cart = Self::find_owned(&shopping_cart_id, &user_id, conn)?;
+
+            PaymentMethodService::find_active(
+                &payload.payment_method_id,
+                &shopping_cart.store_id,
+                conn
+            )?;
+
+            let shipping_method = ShippingService::find_active_method(
+                &payload.shipping_method_id,
+                &shopping_cart.store_id,
+                conn
+            )?;
+
+            let address = AddressService::find(&payload.user_address_id, &user_id, false, conn)?;
+
+            let order_items = OrderItemService::find_by_shopping_cart(&shopping_cart_id, conn)?;
+
+            if order_items.is_empty() {
+                return Err(ServiceError::BadRequest {
+                    error_message: "Shopping cart is empty".to_string(),
+                });
+            }
+
+            let mut total_price = BigDecimal::zero();
+            let mut total_discount = BigDecimal::zero();
+            let mut weight_grams: i64 = 0;
+
+            for order_item in &order_items {
+                let product_item = ProductItemService::find_for_update(
+                    &order_item.product_item_id,
+                    conn
+                )?;
+
+                Self::check_same_store(&product_item, &shopping_cart.store_id)?;
+
+                StockReservationService::check_available(
+                    &product_item,
+                    Some(order_item.id),
+                    &order_item.quantity,
+                    conn
+                )?;
+
+                let quantity = BigDecimal::from(order_item.quantity);
+                let unit_price = ProductDiscountService::effective_price(&product_item, conn)?;
+
+                if unit_price != order_item.unit_price {
+                    OrderItemService::update_unit_price(&unit_price, &order_item.id, conn)?;
+                }
+
+                total_discount += (&product_item.price - &unit_price) * &quantity;
+                total_price += unit_price * quantity;
+                weight_grams += i64::from(product_item.weight_grams.unwrap_or(0)) * i64::from(order_item.quantity);
+
+                ProductItemService::decrement_stock(&product_item.id, &order_item.quantity, conn)?;
+            }
+
+            let shipping_price = ShippingService::quote(
+                &shipping_method,
+                &address.country,
+                weight_grams,
+                &total_price,
+                conn
+            )?;
+
+            let promo_discount = match shopping_cart.promo_code_id {
+                Some(promo_code_id) => {
+                    let discount = PromoCodeService::checkout_discount(
+                        &promo_code_id,
+                        &shopping_cart_id,
+                        &shopping_cart.store_id,
+                        &user_id,
+                        conn
+                    )?;
+
+                    total_discount += &discount;
+                    total_price -= &discount;
+
+                    Some((promo_code_id, discount))
+                }
+                None => None,
+            };
+
+            total_price += &shipping_price;
+
+            let shipping_information_id = ShippingService::create_information(
+                &address,
+                &shipping_method.id,
+                shipping_price,
+                conn
+            )?;
+
+            let order_id = OrderService::create(
+                InsertableOrder {
+                    status: OrderStatus::PendingPayment as i16,
+                    total_price,
+                    total_discount: if total_discount.is_zero() {
+                        None
+                    } else {
+                        Some(total_discount)
+                    },
+                    customer_id: user_id,
+                    store_id: shopping_cart.store_id,
+                    payment_method_id: payload.payment_method_id,
+                    shipping_information_id,
+                },
+                conn
+            )?;
+
+            OrderService::record_status(
+                &order_id,
+                None,
+                OrderStatus::PendingPayment,
+                &user_id,
+                conn
+            )?;
+
+            if let Some((promo_code_id, discount)) = promo_discount {
+                PromoCodeService::redeem(&promo_code_id, &user_id, &order_id, discount, conn)?;
+            }
+
+            StockReservationService::release_by_shopping_cart(&shopping_cart_id, conn)?;
+
+            OrderItemService::move_to_order(&shopping_cart_id, &order_id, conn)?;
+
+            Self::delete(&shopping_cart_id, &user_id, conn)?;
+
+            Ok(IDResponse { id: order_id })
+        })
+    }
 }
@@ -0,0 +1,115 @@
+use std::env;
+
+use crate::{
+    models::{ product::ProductItem, stock_reservation::ReservedQuantity },
+    error::ServiceError,
+};
+use super::Connection;
+use diesel::sql_types::{ Integer, Nullable };
+use diesel::{ prelude::*, sql_query };
+
+const DEFAULT_RESERVATION_MINUTES: i32 = 15;
+
+pub struct StockReservationService;
+
+impl StockReservationService {
+    fn reservation_minutes() -> i32 {
+        env::var("STOCK_RESERVATION_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse().ok())
+            .unwrap_or(DEFAULT_RESERVATION_MINUTES)
+    }
+
+    pub fn reserved_by_others(
+        product_item_id: &i32,
+        order_item_id: Option<i32>,
+        conn: &mut Connection
+    ) -> Result<i32, ServiceError> {
+        let reserved_result = sql_query(
+            "SELECT COALESCE(SUM(quantity), 0)::INTEGER AS reserved
+            FROM public.stock_reservation
+            WHERE product_item_id = $1
+            AND expires_at > now()
+            AND ($2 IS NULL OR order_item_id <> $2)"
+        )
+            .bind::<Integer, _>(product_item_id)
+            .bind::<Nullable<Integer>, _>(order_item_id)
+            .get_result::<ReservedQuantity>(conn);
+
+        match reserved_result {
+            Ok(result) => Ok(result.reserved),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    /// Expects `product_item` to be locked with `ProductItemService::find_for_update`
+    /// so that concurrent carts can't both see the same units as available.
+    pub fn check_available(
+        product_item: &ProductItem,
+        order_item_id: Option<i32>,
+        quantity: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let reserved = Self::reserved_by_others(&product_item.id, order_item_id, conn)?;
+
+        if *quantity > product_item.stock - reserved {
+            return Err(ServiceError::Forbidden {
+                error_message: "Quantity not available".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn reserve(
+        product_item_id: &i32,
+        order_item_id: &i32,
+        quantity: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let reserve_result = sql_query(
+            "INSERT INTO public.stock_reservation (product_item_id, order_item_id, quantity, expires_at)
+            VALUES ($1, $2, $3, now() + $4 * INTERVAL '1 minute')
+            ON CONFLICT (order_item_id)
+            DO UPDATE SET quantity = EXCLUDED.quantity, expires_at = EXCLUDED.expires_at"
+        )
+            .bind::<Integer, _>(product_item_id)
+            .bind::<Integer, _>(order_item_id)
+            .bind::<Integer, _>(quantity)
+            .bind::<Integer, _>(Self::reservation_minutes())
+            .execute(conn);
+
+        match reserve_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn release_by_shopping_cart(
+        shopping_cart_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        let release_result = sql_query(
+            "DELETE FROM public.stock_reservation
+            WHERE order_item_id IN (SELECT id FROM public.order_item WHERE shopping_cart_id = $1)"
+        )
+            .bind::<Integer, _>(shopping_cart_id)
+            .execute(conn);
+
+        match release_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    pub fn release_expired(conn: &mut Connection) -> Result<usize, ServiceError> {
+        let release_result = sql_query(
+            "DELETE FROM public.stock_reservation WHERE expires_at <= now()"
+        ).execute(conn);
+
+        match release_result {
+            Ok(released) => Ok(released),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
@@ -1,92 +1,534 @@
 use crate::{
-    controllers::store::NewStorePayload,
+    controllers::store::{ NewStorePayload, EditStoreBody, StoreInviteBody, StoreRoleBody },
     models::{
+        image::Image,
         response::IDResponse,
-        store::{ InsertableStore, StoreInvite, InsertableStoreInvite },
-        user::{ InsertableUser, UserType },
+        store::{
+            Store,
+            InsertableStore,
+            StoreChangeset,
+            StoreProfile,
+            StoreAdmin,
+            StoreInvite,
+            InsertableStoreInvite,
+        },
+        user::{ User, InsertableUser, UserType },
         payment_method::InsertablePaymentMethod,
         shipping::InsertableShippingMethod,
+        role::{ StoreRole, Permission },
+    },
+    utils::{
+        password_hash::PasswordHash,
+        slug::{ slugify, numbered_slug },
+        upload::{ process_image, UploadedFile },
+        validation::validate,
     },
-    utils::{ password_hash::PasswordHash, validation::validate },
     error::ServiceError,
+    mail::{ Mail, MailTransport },
+    storage::FileStorage,
+};
+use diesel::{
+    prelude::*,
+    dsl::{ now, IntervalDsl },
+    pg::data_types::PgInterval,
+    sql_query,
+    sql_types::Text,
+    result::{ DatabaseErrorKind, Error as DieselError },
+    Connection as _,
 };
-use diesel::prelude::*;
 use rand::distributions::{ Alphanumeric, DistString };
-use super::{ Connection, user::UserService };
+use super::{
+    Connection,
+    user::UserService,
+    email_verification::EmailVerificationService,
+    image::ImageService,
+};
+
+const DEFAULT_STORE_INVITE_HOURS: i32 = 72;
 
 pub struct StoreService;
 
 impl StoreService {
-    pub fn check_store_admin(
+    /// Role of `user_id` in `store_id`, `None` when they aren't one of its admins.
+    pub fn find_role(
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Option<StoreRole>, ServiceError> {
+        use crate::schema::user::dsl;
+
+        let store_role = dsl::user
+            .filter(dsl::id.eq(user_id))
+            .filter(dsl::managed_store_id.eq(store_id))
+            .filter(dsl::type_.eq(UserType::ADMIN as i32))
+            .select(dsl::store_role)
+            .first::<Option<i16>>(conn)
+            .optional()?
+            .flatten()
+            .map(StoreRole::from_i16);
+
+        Ok(store_role)
+    }
+
+    /// The single gate for store management: the user has to be an admin of `store_id`
+    /// whose role grants `permission`. Roles are read from the database rather than the
+    /// token so a role change applies right away.
+    pub fn check_permission(
+        store_id: &i32,
+        user_id: &i32,
+        permission: Permission,
+        conn: &mut Connection
+    ) -> Result<StoreRole, ServiceError> {
+        match Self::find_role(store_id, user_id, conn)? {
+            Some(role) if role.has_permission(permission) => {
+                EmailVerificationService::check_verified(user_id, conn)?;
+
+                Ok(role)
+            }
+            Some(_) =>
+                Err(ServiceError::Forbidden {
+                    error_message: "Your store role doesn't allow this action".to_string(),
+                }),
+            None =>
+                Err(ServiceError::Forbidden {
+                    error_message: "User without permissions for this store".to_string(),
+                }),
+        }
+    }
+
+    fn map_write_error(e: DieselError) -> ServiceError {
+        match e {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) =>
+                ServiceError::BadRequest {
+                    error_message: "Store slug is already taken".to_string(),
+                },
+            _ => ServiceError::InternalServerError { error_message: e.to_string() },
+        }
+    }
+
+    /// Slug for a new store, numbered when another store already took it.
+    fn available_slug(name: &str, conn: &mut Connection) -> Result<String, ServiceError> {
+        use crate::schema::store::dsl;
+
+        let base_slug = match slugify(name) {
+            slug if slug.is_empty() => "store".to_string(),
+            slug => slug,
+        };
+
+        let taken = dsl::store
+            .filter(dsl::slug.eq(&base_slug).or(dsl::slug.like(format!("{}-%", base_slug))))
+            .select(dsl::slug)
+            .load::<String>(conn)?;
+
+        Ok(numbered_slug(&base_slug, &taken))
+    }
+
+    pub fn find(store_id: &i32, conn: &mut Connection) -> Result<Store, ServiceError> {
+        use crate::schema::store::dsl;
+
+        dsl::store
+            .find(store_id)
+            .first::<Store>(conn)
+            .optional()?
+            .ok_or(ServiceError::NotFound { error_message: "Store not found".to_string() })
+    }
+
+    pub fn find_by_slug(slug: &str, conn: &mut Connection) -> Result<Store, ServiceError> {
+        use crate::schema::store::dsl;
+
+        dsl::store
+            .filter(dsl::slug.eq(slug))
+            .first::<Store>(conn)
+            .optional()?
+            .ok_or(ServiceError::NotFound { error_message: "Store not found".to_string() })
+    }
+
+    pub fn profile(
+        store: Store,
+        viewer_id: Option<i32>,
+        conn: &mut Connection
+    ) -> Result<StoreProfile, ServiceError> {
+        let viewer_role = match viewer_id {
+            Some(viewer_id) => Self::find_role(&store.id, &viewer_id, conn)?,
+            None => None,
+        };
+
+        let logo = match store.logo_image_id {
+            Some(logo_image_id) => Some(ImageService::find(&logo_image_id, conn)?),
+            None => None,
+        };
+
+        Ok(StoreProfile::new(store, logo, viewer_role))
+    }
+
+    pub fn edit(
+        payload: EditStoreBody,
         store_id: &i32,
         user_id: &i32,
         conn: &mut Connection
     ) -> Result<(), ServiceError> {
-        use crate::schema::{ user, store };
-
-        let store_admins: QueryResult<Vec<i32>> = store::table
-            .inner_join(user::table)
-            .filter(user::dsl::managed_store_id.eq(store_id))
-            .select(user::dsl::id)
-            .load::<i32>(conn);
-
-        match store_admins {
-            Ok(admin_ids) => {
-                if admin_ids.is_empty() {
-                    return Err(ServiceError::NotFound {
-                        error_message: "Store not found".to_string(),
-                    });
-                } else if admin_ids.contains(&user_id) {
-                    return Ok(());
-                } else {
-                    Err(ServiceError::Forbidden {
-                        error_message: "User without permissions for this store".to_string(),
-                    })
-                }
+        use crate::schema::store::dsl;
+
+        validate(&payload)?;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        diesel
+            ::update(dsl::store)
+            .filter(dsl::id.eq(store_id))
+            .set((
+                StoreChangeset {
+                    name: payload.name,
+                    logo_url: payload.logo_url,
+                    slug: payload.slug,
+                    currency: payload.currency,
+                    contact_email: payload.contact_email,
+                    description: payload.description,
+                },
+                dsl::updated_at.eq(now),
+            ))
+            .execute(conn)
+            .map_err(Self::map_write_error)?;
+
+        Ok(())
+    }
+
+    /// Deletes the store's uploaded logo image, leaving `store.logo_image_id` unset.
+    fn take_logo(store_id: &i32, conn: &mut Connection) -> Result<Option<Image>, ServiceError> {
+        use crate::schema::{ image, store::dsl };
+
+        let logo_image_id = dsl::store
+            .find(store_id)
+            .select(dsl::logo_image_id)
+            .for_update()
+            .first::<Option<i32>>(conn)?;
+
+        match logo_image_id {
+            Some(logo_image_id) =>
+                Ok(
+                    Some(
+                        diesel
+                            ::delete(image::dsl::image.find(logo_image_id))
+                            .returning(Image::as_returning())
+                            .get_result(conn)?
+                    )
+                ),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces the store's logo with an uploaded image.
+    pub fn set_logo(
+        file: UploadedFile,
+        store_id: &i32,
+        user_id: &i32,
+        storage: &dyn FileStorage,
+        conn: &mut Connection
+    ) -> Result<Image, ServiceError> {
+        use crate::schema::store::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let logo = ImageService::create(process_image(file)?, store_id, storage, conn)?;
+
+        let update_result = conn.transaction(|conn| {
+            let previous_logo = Self::take_logo(store_id, conn)?;
+
+            diesel
+                ::update(dsl::store)
+                .filter(dsl::id.eq(store_id))
+                .set((
+                    dsl::logo_image_id.eq(logo.id),
+                    dsl::logo_url.eq(&logo.url),
+                    dsl::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+
+            Ok::<_, ServiceError>(previous_logo)
+        });
+
+        match update_result {
+            Ok(previous_logo) => {
+                ImageService::delete_files(previous_logo.as_slice(), storage);
+
+                Ok(logo)
+            }
+            Err(e) => {
+                ImageService::delete_images(&[logo], storage, conn);
+
+                Err(e)
             }
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
         }
     }
 
+    pub fn remove_logo(
+        store_id: &i32,
+        user_id: &i32,
+        storage: &dyn FileStorage,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::store::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStore, conn)?;
+
+        let logo = conn.transaction(|conn| {
+            let logo = Self::take_logo(store_id, conn)?.ok_or(ServiceError::NotFound {
+                error_message: "Store has no uploaded logo".to_string(),
+            })?;
+
+            // a logo URL set by hand afterwards isn't the upload's to clear
+            diesel
+                ::update(dsl::store)
+                .filter(dsl::id.eq(store_id))
+                .filter(dsl::logo_url.eq(&logo.url))
+                .set((dsl::logo_url.eq(None::<String>), dsl::updated_at.eq(now)))
+                .execute(conn)?;
+
+            Ok::<_, ServiceError>(logo)
+        })?;
+
+        ImageService::delete_files(&[logo], storage);
+
+        Ok(())
+    }
+
+    pub fn list_admins(
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<StoreAdmin>, ServiceError> {
+        use crate::schema::user::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ViewStaff, conn)?;
+
+        let admins = dsl::user
+            .filter(dsl::managed_store_id.eq(store_id))
+            .filter(dsl::type_.eq(UserType::ADMIN as i32))
+            .order(dsl::id.asc())
+            .load::<User>(conn)?
+            .into_iter()
+            .map(|admin| StoreAdmin {
+                id: admin.id,
+                email: admin.email,
+                first_name: admin.first_name,
+                last_name: admin.last_name,
+                role: admin.store_role.map(StoreRole::from_i16),
+                email_verified: admin.email_verified_at.is_some(),
+                last_login: admin.last_login,
+            })
+            .collect();
+
+        Ok(admins)
+    }
+
+    fn invite_lifetime(hours: Option<i32>) -> PgInterval {
+        hours
+            .or_else(|| std::env::var("STORE_INVITE_HOURS").ok().and_then(|hours| hours.parse().ok()))
+            .unwrap_or(DEFAULT_STORE_INVITE_HOURS)
+            .hours()
+    }
+
+    /// Creates an invite code. Invites addressed to an email are mailed right away and
+    /// can only be redeemed by a signup with that same address.
     pub fn create_store_invite(
+        payload: StoreInviteBody,
         store_id: &i32,
+        user_id: &i32,
+        mailer: &dyn MailTransport,
         conn: &mut Connection
     ) -> Result<IDResponse<String>, ServiceError> {
-        use crate::schema::store_invite;
+        use crate::schema::{ store, store_invite };
+
+        validate(&payload)?;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStaff, conn)?;
 
         let invite_id = Alphanumeric.sample_string(&mut rand::thread_rng(), 12);
 
-        let new_invite_result = diesel
-            ::insert_into(store_invite::dsl::store_invite)
-            .values(InsertableStoreInvite {
-                id: invite_id.to_owned(),
-                store_id: store_id.to_owned(),
-            })
-            .execute(conn);
+        let role = payload.role.unwrap_or(StoreRole::Manager);
+
+        conn.transaction(|conn| {
+            diesel
+                ::insert_into(store_invite::dsl::store_invite)
+                .values((
+                    InsertableStoreInvite {
+                        id: invite_id.to_owned(),
+                        store_id: store_id.to_owned(),
+                        role: role as i16,
+                        email: payload.email.clone(),
+                        max_uses: payload.max_uses.unwrap_or(1),
+                    },
+                    store_invite::dsl::expires_at.eq(
+                        now + Self::invite_lifetime(payload.expires_in_hours)
+                    ),
+                ))
+                .execute(conn)?;
+
+            if let Some(email) = &payload.email {
+                let store_name = store::table
+                    .find(store_id)
+                    .select(store::dsl::name)
+                    .first::<String>(conn)?;
+
+                mailer.send(
+                    &(Mail {
+                        to: email.to_owned(),
+                        subject: format!("You've been invited to manage {}", store_name),
+                        body: format!(
+                            "You've been invited to join {} as {}.\n\nSign up as an admin with the following invite code:\n\n{}",
+                            store_name,
+                            role.to_string().replace('_', " "),
+                            invite_id
+                        ),
+                    })
+                )?;
+            }
+
+            Ok(IDResponse { id: invite_id.to_owned() })
+        })
+    }
+
+    pub fn list_store_invites(
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<Vec<StoreInvite>, ServiceError> {
+        use crate::schema::store_invite::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStaff, conn)?;
+
+        let invites = dsl::store_invite
+            .filter(dsl::store_id.eq(store_id))
+            .order(dsl::created_at.desc())
+            .load::<StoreInvite>(conn)?;
+
+        Ok(invites)
+    }
+
+    pub fn revoke_store_invite(
+        invite_id: &str,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::store_invite::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStaff, conn)?;
+
+        let revoked = diesel
+            ::update(dsl::store_invite)
+            .filter(dsl::id.eq(invite_id))
+            .filter(dsl::store_id.eq(store_id))
+            .set(dsl::valid.eq(false))
+            .execute(conn)?;
 
-        match new_invite_result {
-            Ok(_) => Ok(IDResponse { id: invite_id }),
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        if revoked == 0 {
+            return Err(ServiceError::NotFound {
+                error_message: "Store invite not found".to_string(),
+            });
         }
+
+        Ok(())
     }
 
-    pub fn check_store_invite(invite_code: &str, conn: &mut Connection) -> Option<i32> {
-        use crate::schema::store_invite;
+    /// Redeems one use of an invite, invalidating it once it runs out. Meant to run in
+    /// the signup transaction so a failed signup doesn't burn the invite.
+    pub fn consume_store_invite(
+        invite_code: &str,
+        email: &str,
+        conn: &mut Connection
+    ) -> Result<StoreInvite, ServiceError> {
+        let invite = sql_query(
+            "SELECT * FROM public.store_invite
+            WHERE id = $1
+            AND valid = true
+            AND expires_at > now()
+            AND uses < max_uses
+            FOR UPDATE"
+        )
+            .bind::<Text, _>(invite_code)
+            .get_result::<StoreInvite>(conn)
+            .optional()?;
+
+        let invite = match invite {
+            Some(invite) if
+                invite.email.as_ref().is_none_or(|invited| invited.eq_ignore_ascii_case(email))
+            => invite,
+            _ => {
+                return Err(ServiceError::Forbidden {
+                    error_message: "Invite code is not valid".to_string(),
+                });
+            }
+        };
+
+        sql_query(
+            "UPDATE public.store_invite
+            SET uses = uses + 1, valid = uses + 1 < max_uses
+            WHERE id = $1"
+        )
+            .bind::<Text, _>(&invite.id)
+            .execute(conn)?;
+
+        Ok(invite)
+    }
+
+    /// Changes the role of another admin of the store. A store always keeps at least one
+    /// owner, so the last one can't be demoted.
+    pub fn update_admin_role(
+        payload: StoreRoleBody,
+        admin_id: &i32,
+        store_id: &i32,
+        user_id: &i32,
+        conn: &mut Connection
+    ) -> Result<(), ServiceError> {
+        use crate::schema::user::dsl;
+
+        Self::check_permission(store_id, user_id, Permission::ManageStaff, conn)?;
+
+        conn.transaction(|conn| {
+            let admins = dsl::user
+                .filter(dsl::managed_store_id.eq(store_id))
+                .filter(dsl::type_.eq(UserType::ADMIN as i32))
+                .select((dsl::id, dsl::store_role))
+                .for_update()
+                .load::<(i32, Option<i16>)>(conn)?;
+
+            let current_role = admins
+                .iter()
+                .find(|(id, _)| id == admin_id)
+                .map(|(_, role)| role.map(StoreRole::from_i16))
+                .ok_or(ServiceError::NotFound {
+                    error_message: "Store admin not found".to_string(),
+                })?;
 
-        let result: QueryResult<StoreInvite> = store_invite::dsl::store_invite
-            .find(invite_code)
-            .first::<StoreInvite>(conn);
+            let owners = admins
+                .iter()
+                .filter(|(_, role)| role.map(StoreRole::from_i16) == Some(StoreRole::Owner))
+                .count();
 
-        match result {
-            Ok(result) => {
-                if result.valid { Some(result.store_id) } else { None }
+            if
+                current_role == Some(StoreRole::Owner) &&
+                payload.role != StoreRole::Owner &&
+                owners <= 1
+            {
+                return Err(ServiceError::BadRequest {
+                    error_message: "A store needs at least one owner".to_string(),
+                });
             }
-            Err(_) => None,
-        }
+
+            diesel
+                ::update(dsl::user)
+                .filter(dsl::id.eq(admin_id))
+                .set((dsl::store_role.eq(payload.role as i16), dsl::updated_at.eq(diesel::dsl::now)))
+                .execute(conn)?;
+
+            Ok(())
+        })
     }
 
     pub fn create(
         payload: NewStorePayload,
+        mailer: &dyn MailTransport,
         conn: &mut Connection
     ) -> Result<IDResponse<i32>, ServiceError> {
         use crate::schema::{ store, user, payment_method, shipping_method };
@@ -101,88 +543,99 @@ impl StoreService {
             });
         }
 
-        let new_store = InsertableStore {
-            name: payload.store_name,
-            logo_url: payload.logo_img,
-        };
+        let (new_store_id, new_user, verification_token) = conn.transaction::<_, ServiceError, _>(|conn| {
+            let new_store = InsertableStore {
+                slug: Self::available_slug(&payload.store_name, conn)?,
+                name: payload.store_name,
+                logo_url: payload.logo_img,
+            };
 
-        match
-            diesel
-                ::insert_into(store::dsl::store)
-                .values(new_store)
-                .returning(store::dsl::id)
-                .get_result::<i32>(conn)
-        {
-            Ok(new_store_id) => {
-                let hashed_password = PasswordHash::create_hash(&payload.password);
-
-                let new_user = InsertableUser {
-                    email: payload.email,
-                    first_name: payload.first_name,
-                    last_name: payload.last_name,
-                    password: hashed_password.password_hash,
-                    salt: hashed_password.salt,
-                    type_: UserType::ADMIN as i32,
-                    managed_store_id: Some(new_store_id),
-                };
-
-                let user_result = diesel
-                    ::insert_into(user::dsl::user)
-                    .values(new_user)
-                    .execute(conn);
-
-                if user_result.is_err() {
-                    return Err(ServiceError::InternalServerError {
-                        error_message: user_result.unwrap_err().to_string(),
-                    });
-                }
+            match
+                diesel
+                    ::insert_into(store::dsl::store)
+                    .values(new_store)
+                    .returning(store::dsl::id)
+                    .get_result::<i32>(conn)
+            {
+                Ok(new_store_id) => {
+                    let hashed_password = PasswordHash::create_hash(&payload.password);
 
-                let default_payment_methods: Vec<InsertablePaymentMethod> = vec![
-                    "Bank transfer",
-                    "Credit card",
-                    "Debit card",
-                    "PayPal",
-                    "Cash"
-                ]
-                    .into_iter()
-                    .map(|method| InsertablePaymentMethod {
-                        name: method.to_string(),
-                        store_id: new_store_id,
-                    })
-                    .collect();
+                    let new_user = InsertableUser {
+                        email: payload.email,
+                        first_name: payload.first_name,
+                        last_name: payload.last_name,
+                        password: hashed_password.password_hash,
+                        salt: hashed_password.salt,
+                        type_: UserType::ADMIN as i32,
+                        managed_store_id: Some(new_store_id),
+                        store_role: Some(StoreRole::Owner as i16),
+                    };
 
-                let payment_method_result = diesel
-                    ::insert_into(payment_method::dsl::payment_method)
-                    .values(default_payment_methods)
-                    .execute(conn);
+                    let user_result = diesel
+                        ::insert_into(user::dsl::user)
+                        .values(new_user)
+                        .get_result::<User>(conn);
 
-                if payment_method_result.is_err() {
-                    return Err(ServiceError::InternalServerError {
-                        error_message: payment_method_result.unwrap_err().to_string(),
-                    });
-                }
+                    let new_user = match user_result {
+                        Ok(new_user) => new_user,
+                        Err(e) => {
+                            return Err(ServiceError::InternalServerError {
+                                error_message: e.to_string(),
+                            });
+                        }
+                    };
 
-                let default_shipping_methods: Vec<InsertableShippingMethod> = vec!["Ups", "FedEx"]
-                    .into_iter()
-                    .map(|method| InsertableShippingMethod {
-                        name: method.to_string(),
-                        store_id: new_store_id,
-                    })
-                    .collect();
+                    let default_payment_methods: Vec<InsertablePaymentMethod> = vec![
+                        "Bank transfer",
+                        "Credit card",
+                        "Debit card",
+                        "PayPal",
+                        "Cash"
+                    ]
+                        .into_iter()
+                        .map(|method| InsertablePaymentMethod {
+                            name: method.to_string(),
+                            store_id: new_store_id,
+                        })
+                        .collect();
+
+                    diesel
+                        ::insert_into(payment_method::dsl::payment_method)
+                        .values(default_payment_methods)
+                        .execute(conn)
+                        .map_err(|e| ServiceError::InternalServerError {
+                            error_message: e.to_string(),
+                        })?;
+
+                    let default_shipping_methods: Vec<InsertableShippingMethod> = vec!["Ups", "FedEx"]
+                        .into_iter()
+                        .map(|method| InsertableShippingMethod {
+                            name: method.to_string(),
+                            store_id: new_store_id,
+                        })
+                        .collect();
 
-                let shipping_method_result = diesel
-                    ::insert_into(shipping_method::dsl::shipping_method)
-                    .values(default_shipping_methods)
-                    .execute(conn);
+                    diesel
+                        ::insert_into(shipping_method::dsl::shipping_method)
+                        .values(default_shipping_methods)
+                        .execute(conn)
+                        .map_err(|e| ServiceError::InternalServerError {
+                            error_message: e.to_string(),
+                        })?;
 
-                if shipping_method_result.is_err() {
-                    return Err(ServiceError::InternalServerError {
-                        error_message: shipping_method_result.unwrap_err().to_string(),
-                    });
+                    let verification_token = EmailVerificationService::create_token(
+                        &new_user.id,
+                        conn
+                    )?;
+
+                    Ok((new_store_id, new_user, verification_token))
                 }
-                return Ok(IDResponse { id: new_store_id });
+                Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
             }
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
-        }
+        })?;
+
+        EmailVerificationService::send_after_signup(&new_user, &verification_token, mailer);
+
+        Ok(IDResponse { id: new_store_id })
     }
 }
@@ -1,13 +1,19 @@
 use std::time::SystemTime;
 
-use super::{ Connection, store::StoreService };
+use super::{
+    Connection,
+    store::StoreService,
+    session::SessionService,
+    email_verification::EmailVerificationService,
+};
 use crate::{
     models::{ user::{ User, InsertableUser, UserType }, response::{ TokenResponse, IDResponse } },
-    controllers::auth::{ UserSignupPayload, UserLoginPayload },
+    controllers::auth::{ UserSignupPayload, UserLoginPayload, RefreshTokenPayload },
     utils::{ password_hash::PasswordHash, jwt_auth::TokenClaims, validation::validate },
     error::ServiceError,
+    mail::MailTransport,
 };
-use diesel::{ RunQueryDsl, ExpressionMethods, QueryDsl };
+use diesel::{ RunQueryDsl, ExpressionMethods, QueryDsl, Connection as _ };
 
 pub struct UserService;
 
@@ -22,8 +28,39 @@ impl UserService {
         }
     }
 
+    pub fn find(user_id: &i32, conn: &mut Connection) -> Result<User, ServiceError> {
+        use crate::schema::user::dsl::*;
+        let user_result = user.find(user_id).get_result(conn);
+
+        match user_result {
+            Ok(found_user) => Ok(found_user),
+            Err(_) => Err(ServiceError::NotFound { error_message: "User not found!".to_string() }),
+        }
+    }
+
+    fn issue_tokens(
+        existing_user: &User,
+        session_id: i32,
+        refresh_token: String
+    ) -> Result<TokenResponse, ServiceError> {
+        let token_claims = TokenClaims::new(
+            existing_user.type_,
+            &existing_user.id.to_string(),
+            &existing_user.first_name,
+            &existing_user.last_name,
+            existing_user.managed_store_id,
+            session_id,
+        );
+
+        match token_claims.sign_token() {
+            Ok(token) => Ok(TokenResponse { token, refresh_token }),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
     pub fn signup(
         payload: UserSignupPayload,
+        mailer: &dyn MailTransport,
         conn: &mut Connection
     ) -> Result<IDResponse<i32>, ServiceError> {
         use crate::schema::user::dsl::*;
@@ -40,55 +77,56 @@ impl UserService {
 
         let hashed_password = PasswordHash::create_hash(&payload.password);
 
-        let insertable_user: InsertableUser;
-
-        match payload.type_ {
-            UserType::ADMIN => {
-                if let Some(invite_code) = payload.invite_code {
-                    if let Some(store_id) = StoreService::check_store_invite(&invite_code, conn) {
-                        insertable_user = InsertableUser {
-                            email: payload.email,
-                            first_name: payload.first_name,
-                            last_name: payload.last_name,
-                            managed_store_id: Some(store_id),
-                            password: hashed_password.password_hash,
-                            salt: hashed_password.salt,
-                            type_: UserType::ADMIN as i32,
-                        };
-                    } else {
-                        return Err(ServiceError::Forbidden {
-                            error_message: "Invite code is not valid".to_string(),
-                        });
-                    }
-                } else {
-                    return Err(ServiceError::Forbidden {
+        let (new_user, verification_token) = conn.transaction::<_, ServiceError, _>(|conn| {
+            let insertable_user = match payload.type_ {
+                UserType::ADMIN => {
+                    let invite_code = payload.invite_code.ok_or(ServiceError::Forbidden {
                         error_message: "Invite code is required for admin users".to_string(),
-                    });
+                    })?;
+
+                    let invite = StoreService::consume_store_invite(
+                        &invite_code,
+                        &payload.email,
+                        conn
+                    )?;
+
+                    InsertableUser {
+                        email: payload.email,
+                        first_name: payload.first_name,
+                        last_name: payload.last_name,
+                        managed_store_id: Some(invite.store_id),
+                        store_role: Some(invite.role),
+                        password: hashed_password.password_hash,
+                        salt: hashed_password.salt,
+                        type_: UserType::ADMIN as i32,
+                    }
                 }
-            }
-            UserType::CUSTOMER => {
-                insertable_user = InsertableUser {
-                    email: payload.email,
-                    first_name: payload.first_name,
-                    last_name: payload.last_name,
-                    managed_store_id: None,
-                    password: hashed_password.password_hash,
-                    salt: hashed_password.salt,
-                    type_: UserType::CUSTOMER as i32,
-                };
-            }
-        }
-
-        let result = diesel
-            ::insert_into(user)
-            .values(insertable_user)
-            .returning(id)
-            .get_result::<i32>(conn);
-
-        match result {
-            Ok(new_id) => Ok(IDResponse { id: new_id }),
-            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
-        }
+                UserType::CUSTOMER =>
+                    InsertableUser {
+                        email: payload.email,
+                        first_name: payload.first_name,
+                        last_name: payload.last_name,
+                        managed_store_id: None,
+                        store_role: None,
+                        password: hashed_password.password_hash,
+                        salt: hashed_password.salt,
+                        type_: UserType::CUSTOMER as i32,
+                    },
+            };
+
+            let new_user = diesel
+                ::insert_into(user)
+                .values(insertable_user)
+                .get_result::<User>(conn)?;
+
+            let verification_token = EmailVerificationService::create_token(&new_user.id, conn)?;
+
+            Ok((new_user, verification_token))
+        })?;
+
+        EmailVerificationService::send_after_signup(&new_user, &verification_token, mailer);
+
+        Ok(IDResponse { id: new_user.id })
     }
 
     pub fn login(
@@ -122,17 +160,52 @@ impl UserService {
                 .execute(conn)
                 .expect("Error updating last login");
 
-            let token_claims = TokenClaims::new(
-                existing_user.type_,
-                &existing_user.id.to_string(),
-                &existing_user.first_name,
-                &existing_user.last_name,
-                existing_user.managed_store_id,
-            );
+            if user_password.needs_rehash() {
+                let upgraded_password = PasswordHash::create_hash(&payload.password);
+
+                diesel
+                    ::update(user)
+                    .filter(id.eq(existing_user.id))
+                    .set((
+                        password.eq(upgraded_password.password_hash),
+                        salt.eq(upgraded_password.salt),
+                    ))
+                    .execute(conn)?;
+            }
+
+            let (session_id, refresh_token) = SessionService::create(&existing_user.id, conn)?;
+
+            Self::issue_tokens(&existing_user, session_id, refresh_token)
+        }
+    }
+
+    pub fn refresh(
+        payload: RefreshTokenPayload,
+        conn: &mut Connection
+    ) -> Result<TokenResponse, ServiceError> {
+        validate(&payload)?;
 
-            let token_str = token_claims.sign_token().unwrap();
+        let token_res = conn.transaction(|conn| {
+            match SessionService::rotate(&payload.refresh_token, conn)? {
+                Some((session, refresh_token)) => {
+                    let existing_user = Self::find(&session.user_id, conn)?;
 
-            Ok(TokenResponse { token: token_str })
+                    Self::issue_tokens(&existing_user, session.id, refresh_token).map(Some)
+                }
+                None => Ok(None),
+            }
+        })?;
+
+        match token_res {
+            Some(token_res) => Ok(token_res),
+            None =>
+                Err(ServiceError::Unauthorized {
+                    error_message: "Invalid refresh token".to_string(),
+                }),
         }
     }
+
+    pub fn logout(session_id: &i32, conn: &mut Connection) -> Result<(), ServiceError> {
+        SessionService::revoke(session_id, conn)
+    }
 }
@@ -1,7 +1,7 @@
 use actix_web::{ error, http::{ header::ContentType, StatusCode }, HttpResponse };
 use derive_more::{ Display, Error };
 
-use crate::models::response::ResponseBody;
+use crate::models::{ product::BlockingProduct, response::ResponseBody };
 
 #[derive(Debug, Display, Error)]
 pub enum ServiceError {
@@ -24,6 +24,12 @@ pub enum ServiceError {
     #[display(fmt = "{error_message}")] Forbidden {
         error_message: String,
     },
+
+    /// A row can't be removed while products still reference it.
+    #[display(fmt = "{error_message}")] Conflict {
+        error_message: String,
+        blocking_products: Vec<BlockingProduct>,
+    },
 }
 
 impl error::ResponseError for ServiceError {
@@ -33,12 +39,36 @@ impl error::ResponseError for ServiceError {
             ServiceError::InternalServerError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             ServiceError::BadRequest { .. } => StatusCode::BAD_REQUEST,
             ServiceError::NotFound { .. } => StatusCode::NOT_FOUND,
-            ServiceError::Forbidden { .. } => StatusCode::FORBIDDEN
+            ServiceError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            ServiceError::Conflict { .. } => StatusCode::CONFLICT,
         }
     }
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code())
-            .insert_header(ContentType::json())
-            .json(ResponseBody::new_err(&self.to_string()))
+        let mut response = HttpResponse::build(self.status_code());
+
+        response.insert_header(ContentType::json());
+
+        match self {
+            ServiceError::Conflict { error_message, blocking_products } =>
+                response.json(
+                    serde_json::json!({
+                        "error": error_message,
+                        "blocking_products": blocking_products,
+                    })
+                ),
+            _ => response.json(ResponseBody::new_err(&self.to_string())),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for ServiceError {
+    fn from(e: diesel::result::Error) -> Self {
+        ServiceError::InternalServerError { error_message: e.to_string() }
+    }
+}
+
+impl From<error::BlockingError> for ServiceError {
+    fn from(e: error::BlockingError) -> Self {
+        ServiceError::InternalServerError { error_message: e.to_string() }
     }
 }
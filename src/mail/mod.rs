@@ -0,0 +1,27 @@
+pub mod outbox;
+pub mod smtp;
+
+use std::{ env, sync::Arc };
+
+use crate::error::ServiceError;
+use outbox::OutboxMailTransport;
+use smtp::SmtpMailTransport;
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+pub trait MailTransport: Send + Sync {
+    fn send(&self, mail: &Mail) -> Result<(), ServiceError>;
+}
+
+/// Picks the transport from `MAIL_TRANSPORT`. Anything other than `smtp` writes mails
+/// to the local outbox, so development doesn't need a mail server.
+pub fn transport_from_env() -> Arc<dyn MailTransport> {
+    match env::var("MAIL_TRANSPORT").as_deref() {
+        Ok("smtp") => Arc::new(SmtpMailTransport::from_env()),
+        _ => Arc::new(OutboxMailTransport::from_env()),
+    }
+}
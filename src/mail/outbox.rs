@@ -0,0 +1,42 @@
+use std::{ env, fs, path::PathBuf, time::{ SystemTime, UNIX_EPOCH } };
+
+use super::{ Mail, MailTransport };
+use crate::{ error::ServiceError, utils::token::generate_token };
+
+const DEFAULT_OUTBOX_DIR: &str = "mail_outbox";
+
+/// Writes every mail as a `.eml` file into `MAIL_OUTBOX_DIR` instead of delivering it.
+pub struct OutboxMailTransport {
+    dir: PathBuf,
+}
+
+impl OutboxMailTransport {
+    pub fn from_env() -> OutboxMailTransport {
+        OutboxMailTransport {
+            dir: env::var("MAIL_OUTBOX_DIR").unwrap_or(DEFAULT_OUTBOX_DIR.to_string()).into(),
+        }
+    }
+}
+
+impl MailTransport for OutboxMailTransport {
+    fn send(&self, mail: &Mail) -> Result<(), ServiceError> {
+        let sent_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis())
+            .unwrap_or_default();
+
+        // the random suffix keeps mails sent in the same millisecond apart
+        let file_name = format!("{}-{}.eml", sent_at, &generate_token()[..8]);
+
+        let contents = format!("To: {}\nSubject: {}\n\n{}\n", mail.to, mail.subject, mail.body);
+
+        let write_result = fs
+            ::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(file_name), contents));
+
+        match write_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::env;
+
+use lettre::{
+    message::Mailbox,
+    transport::smtp::authentication::Credentials,
+    Message,
+    SmtpTransport,
+    Transport,
+};
+
+use super::{ Mail, MailTransport };
+use crate::error::ServiceError;
+
+pub struct SmtpMailTransport {
+    from: Mailbox,
+    transport: SmtpTransport,
+}
+
+impl SmtpMailTransport {
+    pub fn from_env() -> SmtpMailTransport {
+        let host = env::var("SMTP_HOST").expect("SMTP_HOST must be set");
+
+        let from = env
+            ::var("MAIL_FROM")
+            .expect("MAIL_FROM must be set")
+            .parse::<Mailbox>()
+            .expect("MAIL_FROM must be a valid mailbox");
+
+        // SMTP_TLS=false is meant for local catchers like MailHog
+        let mut builder = match env::var("SMTP_TLS").as_deref() {
+            Ok("false") => SmtpTransport::builder_dangerous(&host),
+            _ =>
+                SmtpTransport::starttls_relay(&host).unwrap_or_else(|_|
+                    panic!("Invalid SMTP host {}", &host)
+                ),
+        };
+
+        if let Some(port) = env::var("SMTP_PORT").ok().and_then(|port| port.parse().ok()) {
+            builder = builder.port(port);
+        }
+
+        if let (Ok(username), Ok(password)) = (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        SmtpMailTransport {
+            from,
+            transport: builder.build(),
+        }
+    }
+}
+
+impl MailTransport for SmtpMailTransport {
+    fn send(&self, mail: &Mail) -> Result<(), ServiceError> {
+        let to = mail.to.parse::<Mailbox>().map_err(|e| ServiceError::BadRequest {
+            error_message: e.to_string(),
+        })?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&mail.subject)
+            .body(mail.body.clone())
+            .map_err(|e| ServiceError::InternalServerError { error_message: e.to_string() })?;
+
+        match self.transport.send(&message) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
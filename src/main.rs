@@ -5,17 +5,26 @@ mod schema;
 mod utils;
 mod controllers;
 mod db;
+mod mail;
+mod storage;
 
 use actix_cors::Cors;
 use actix_web::{ middleware::Logger, web, App, HttpServer };
-use log::info;
-use std::env;
+use db::stock_reservation::StockReservationService;
+use log::{ error, info };
+use std::{ env, time::Duration };
 
 fn routes(app: &mut web::ServiceConfig) {
     app.service(
             web::scope("auth")
                 .service(controllers::auth::signup)
                 .service(controllers::auth::login)
+                .service(controllers::auth::refresh)
+                .service(controllers::auth::logout)
+                .service(controllers::auth::request_password_reset)
+                .service(controllers::auth::confirm_password_reset)
+                .service(controllers::auth::verify_email)
+                .service(controllers::auth::resend_verification_email)
         )
         .service(
             web::scope("addresses")
@@ -29,24 +38,115 @@ fn routes(app: &mut web::ServiceConfig) {
             web::scope("stores")
                 .service(controllers::store::create_store)
                 .service(controllers::store::create_store_invite)
+                .service(controllers::store::list_store_invites)
+                .service(controllers::store::revoke_store_invite)
+                .service(controllers::store::update_admin_role)
+                .service(controllers::order::list_store_orders)
+                .service(controllers::promo_code::create_promo_code)
+                .service(controllers::promo_code::list_promo_codes)
+                .service(controllers::promo_code::edit_promo_code)
+                .service(controllers::promo_code::delete_promo_code)
+                .service(controllers::order::mark_orders_shipped)
+                .service(controllers::order::update_order_status)
+                .service(controllers::order::list_order_status_history)
+                .service(controllers::product::list_store_products)
+                .service(controllers::product::list_store_categories)
+                .service(controllers::product::search_store_products)
+                .service(controllers::payment_method::create_payment_method)
+                .service(controllers::payment_method::list_payment_methods)
+                .service(controllers::payment_method::edit_payment_method)
+                .service(controllers::payment_method::delete_payment_method)
+                .service(controllers::payment_method::list_active_payment_methods)
+                .service(controllers::shipping::create_shipping_method)
+                .service(controllers::shipping::list_shipping_methods)
+                .service(controllers::shipping::edit_shipping_method)
+                .service(controllers::shipping::delete_shipping_method)
+                .service(controllers::shipping::list_active_shipping_methods)
+                .service(controllers::store::edit_store)
+                .service(controllers::store::upload_store_logo)
+                .service(controllers::store::delete_store_logo)
+                .service(controllers::store::list_store_admins)
+                .service(controllers::store::find_store_by_slug)
+                .service(controllers::store::find_store)
         )
         .service(
             web::scope("products")
                 .service(controllers::product::create_product_category)
                 .service(controllers::product::create_product_variant)
+                .service(controllers::product::create_product_option)
+                .service(controllers::product::create_option_value)
                 .service(controllers::product::create_product)
+                .service(controllers::product::import_products)
+                .service(controllers::product::export_products)
                 .service(controllers::product::list_variants)
+                .service(controllers::product::list_options)
                 .service(controllers::product::list_categories)
+                .service(controllers::product::move_product_category)
+                .service(controllers::product::edit_product_category)
+                .service(controllers::product::delete_product_category)
+                .service(controllers::product::edit_product_variant)
+                .service(controllers::product::delete_product_variant)
+                .service(controllers::product::edit_product_option)
+                .service(controllers::product::delete_product_option)
+                .service(controllers::product::create_product_discount)
+                .service(controllers::product::list_product_discounts)
+                .service(controllers::product::edit_product_discount)
+                .service(controllers::product::delete_product_discount)
+                .service(controllers::product::attach_product_discount)
+                .service(controllers::product::find_product)
+                .service(controllers::product::edit_product)
+                .service(controllers::product::delete_product)
+                .service(controllers::product::restore_product)
+                .service(controllers::product::generate_product_items)
+                .service(controllers::product::edit_product_item)
+                .service(controllers::product::delete_product_item)
+                .service(controllers::product::restore_product_item)
+                .service(controllers::product::upload_product_item_images)
+                .service(controllers::product::reorder_product_item_images)
+                .service(controllers::product::delete_product_item_image)
         )
         .service(
             web::scope("shopping-carts")
                 .service(controllers::shopping_cart::create_shopping_cart)
                 .service(controllers::shopping_cart::edit_shopping_cart)
                 .service(controllers::shopping_cart::delete_shopping_cart)
+                .service(controllers::shopping_cart::checkout_shopping_cart)
+                .service(controllers::shopping_cart::quote_shipping)
+                .service(controllers::promo_code::apply_promo_code)
+                .service(controllers::promo_code::remove_promo_code)
+        )
+        .service(
+            web::scope("orders")
+                .service(controllers::order::list_orders)
+                .service(controllers::order::find_order)
         );
         
 }
 
+fn spawn_reservation_sweeper(pool: db::Pool) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            let sweep_pool = pool.clone();
+
+            let sweep_result = web::block(move || {
+                let mut conn = sweep_pool.get().map_err(|e| e.to_string())?;
+                StockReservationService::release_expired(&mut conn).map_err(|e| e.to_string())
+            }).await;
+
+            match sweep_result {
+                Ok(Ok(released)) if released > 0 => info!("Released {} expired stock reservations", released),
+                Ok(Err(e)) => error!("Error releasing expired stock reservations: {}", e),
+                Err(e) => error!("Error releasing expired stock reservations: {}", e),
+                _ => {}
+            }
+        }
+    });
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
@@ -61,6 +161,12 @@ async fn main() -> std::io::Result<()> {
 
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    spawn_reservation_sweeper(pool.clone());
+
+    let mailer = web::Data::from(mail::transport_from_env());
+
+    let storage = web::Data::from(storage::storage_from_env());
+
     info!("logando!");
     HttpServer::new(move || {
 
@@ -71,7 +177,10 @@ async fn main() -> std::io::Result<()> {
             .wrap(cors)
             .wrap(Logger::new("%a %{User-Agent}i"))
             .app_data(web::Data::new(pool.clone()))
+            .app_data(mailer.clone())
+            .app_data(storage.clone())
             .configure(routes)
+            .configure(storage::serve_local_files)
         })
         .bind(&bind_address)
         .unwrap_or_else(|_| panic!("Could not bind to server address {}", &bind_address))
@@ -1,31 +1,159 @@
-use actix_web::FromRequest;
-use futures_util::future::{err, ok, Ready};
+use actix_web::{web, FromRequest, HttpRequest};
+use futures_util::future::{ready, Ready};
 
-use crate::{error::ServiceError, utils::jwt_auth::TokenClaims};
+use crate::{
+    db::{session::SessionService, Pool},
+    error::ServiceError,
+    models::user::UserType,
+    utils::jwt_auth::TokenClaims,
+};
 
 pub struct AuthMiddleware {
     pub user: TokenClaims,
 }
 
+fn authenticate(req: &HttpRequest) -> Result<AuthMiddleware, ServiceError> {
+    let auth = req.headers().get("authorization");
+
+    match auth {
+        Some(header) => {
+            let token = header
+                .to_str()
+                .ok()
+                .and_then(|value| value.split(' ').next_back())
+                .ok_or(ServiceError::Unauthorized {
+                    error_message: "Invalid token!".to_string(),
+                })?;
+
+            match TokenClaims::decode_token(token) {
+                Ok(token_claims) => {
+                    let pool = req.app_data::<web::Data<Pool>>().ok_or(
+                        ServiceError::InternalServerError {
+                            error_message: "Database pool is not configured".to_string(),
+                        }
+                    )?;
+
+                    let mut conn = pool.get().map_err(|e| ServiceError::InternalServerError {
+                        error_message: e.to_string(),
+                    })?;
+
+                    let session_active = SessionService::is_active(&token_claims.sid, &mut conn)?;
+
+                    if session_active {
+                        Ok(AuthMiddleware { user: token_claims })
+                    } else {
+                        Err(ServiceError::Unauthorized {
+                            error_message: "Session has been revoked".to_string(),
+                        })
+                    }
+                }
+                Err(_) => Err(ServiceError::Unauthorized {
+                    error_message: "Invalid token!".to_string(),
+                }),
+            }
+        }
+        None => Err(ServiceError::Unauthorized {
+            error_message: "Authorization header is required".to_string(),
+        }),
+    }
+}
+
+impl AuthMiddleware {
+    fn user_id(&self) -> Result<i32, ServiceError> {
+        self.user.sub.parse().map_err(|_| ServiceError::Unauthorized {
+            error_message: "Invalid token!".to_string(),
+        })
+    }
+}
+
 impl FromRequest for AuthMiddleware {
     type Error = ServiceError;
     type Future = Ready<Result<AuthMiddleware, ServiceError>>;
-    fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
-        let auth = req.headers().get("authorization");
-
-        match auth {
-            Some(_) => {
-                let token = auth.unwrap().to_str().unwrap().split(' ').last().unwrap();
-                match TokenClaims::decode_token(token) {
-                    Ok(token_claims) => ok(AuthMiddleware { user: token_claims }),
-                    Err(_) => err(ServiceError::Unauthorized {
-                        error_message: "Invalid token!".to_string(),
-                    }),
-                }
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        ready(authenticate(req))
+    }
+}
+
+/// Any signed in user, customer or admin.
+pub struct UserAuth {
+    pub user_id: i32,
+    pub session_id: i32,
+}
+
+impl FromRequest for UserAuth {
+    type Error = ServiceError;
+    type Future = Ready<Result<UserAuth, ServiceError>>;
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        ready(authenticate(req).and_then(|auth| {
+            Ok(UserAuth {
+                user_id: auth.user_id()?,
+                session_id: auth.user.sid,
+            })
+        }))
+    }
+}
+
+pub struct CustomerAuth {
+    pub user_id: i32,
+}
+
+impl FromRequest for CustomerAuth {
+    type Error = ServiceError;
+    type Future = Ready<Result<CustomerAuth, ServiceError>>;
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        ready(authenticate(req).and_then(|auth| {
+            if UserType::from_i32(auth.user.type_) != UserType::CUSTOMER {
+                return Err(ServiceError::Forbidden {
+                    error_message: "Only customers can access this resource".to_string(),
+                });
+            }
+
+            Ok(CustomerAuth { user_id: auth.user_id()? })
+        }))
+    }
+}
+
+/// An admin together with the store they manage. What the admin may do in that store
+/// is still up to `StoreService::check_permission`.
+pub struct StoreAdminAuth {
+    pub user_id: i32,
+    pub store_id: i32,
+}
+
+impl FromRequest for StoreAdminAuth {
+    type Error = ServiceError;
+    type Future = Ready<Result<StoreAdminAuth, ServiceError>>;
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        ready(authenticate(req).and_then(|auth| {
+            match (UserType::from_i32(auth.user.type_), auth.user.managed_store_id) {
+                (UserType::ADMIN, Some(store_id)) => Ok(StoreAdminAuth {
+                    user_id: auth.user_id()?,
+                    store_id,
+                }),
+                _ => Err(ServiceError::Forbidden {
+                    error_message: "Only store admins can access this resource".to_string(),
+                }),
             }
-            None => err(ServiceError::Unauthorized {
-                error_message: "Authorization header is required".to_string(),
-            }),
+        }))
+    }
+}
+
+/// For public endpoints that show more to signed in users. A missing header yields
+/// `None`, but a bad token is still rejected so clients notice it.
+pub struct OptionalAuth(pub Option<UserAuth>);
+
+impl FromRequest for OptionalAuth {
+    type Error = ServiceError;
+    type Future = Ready<Result<OptionalAuth, ServiceError>>;
+    fn from_request(req: &HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        if req.headers().get("authorization").is_none() {
+            return ready(Ok(OptionalAuth(None)));
         }
+
+        ready(
+            UserAuth::from_request(req, payload)
+                .into_inner()
+                .map(|user| OptionalAuth(Some(user))),
+        )
     }
 }
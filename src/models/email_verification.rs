@@ -0,0 +1,7 @@
+use diesel::QueryableByName;
+
+#[derive(QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::email_verification_token)]
+pub struct EmailVerificationToken {
+    pub user_id: i32,
+}
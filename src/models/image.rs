@@ -0,0 +1,55 @@
+use chrono::NaiveDateTime;
+use diesel::{ Queryable, Insertable, Selectable };
+use serde::Serialize;
+use crate::schema::{ image, product_item_image };
+
+#[derive(Queryable, Selectable, Serialize, Debug)]
+#[diesel(table_name = image)]
+pub struct Image {
+    pub id: i32,
+    pub url: String,
+    pub thumbnail_url: String,
+    #[serde(skip)]
+    pub storage_key: String,
+    #[serde(skip)]
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub width: i32,
+    pub height: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = image)]
+pub struct InsertableImage {
+    pub url: String,
+    pub thumbnail_url: String,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub width: i32,
+    pub height: i32,
+    pub store_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = product_item_image)]
+pub struct InsertableItemImage {
+    pub product_item_id: i32,
+    pub image_id: i32,
+    pub position: i32,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct ItemImage {
+    #[serde(skip)]
+    pub product_item_id: i32,
+    pub id: i32,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub position: i32,
+}
@@ -6,4 +6,11 @@ pub mod payment_method;
 pub mod shipping;
 pub mod product;
 pub mod order;
-pub mod shopping_cart;
\ No newline at end of file
+pub mod shopping_cart;
+pub mod stock_reservation;
+pub mod promo_code;
+pub mod session;
+pub mod password_reset;
+pub mod email_verification;
+pub mod role;
+pub mod image;
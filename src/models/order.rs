@@ -1,8 +1,13 @@
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
 use diesel::{ Queryable, Insertable, QueryableByName };
+use serde::Serialize;
+use serde_repr::{ Deserialize_repr, Serialize_repr };
+use strum_macros::Display;
+use super::product::CatalogVariant;
 
-#[derive(Queryable, Debug)]
+#[derive(Queryable, QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::order)]
 pub struct Order {
     pub id: i32,
     pub status: i16,
@@ -15,6 +20,18 @@ pub struct Order {
     pub total_price: BigDecimal,
 }
 
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::order)]
+pub struct InsertableOrder {
+    pub status: i16,
+    pub total_price: BigDecimal,
+    pub total_discount: Option<BigDecimal>,
+    pub customer_id: i32,
+    pub store_id: i32,
+    pub payment_method_id: i32,
+    pub shipping_information_id: i32,
+}
+
 #[derive(Queryable, QueryableByName, Debug)]
 #[diesel(table_name = crate::schema::order_item)]
 pub struct OrderItem {
@@ -35,3 +52,204 @@ pub struct InsertableOrderItem {
     pub shopping_cart_id: Option<i32>,
     pub unit_price: BigDecimal,
 }
+
+#[derive(Serialize_repr, Deserialize_repr, Display, PartialEq, Clone, Copy, Debug)]
+#[strum(serialize_all = "snake_case")]
+#[repr(u8)]
+pub enum OrderStatus {
+    PendingPayment,
+    Paid,
+    Fulfilled,
+    Shipped,
+    Delivered,
+    Cancelled,
+    Refunded,
+}
+
+impl OrderStatus {
+    pub fn from_i16(int_status: i16) -> Self {
+        match int_status {
+            0 => OrderStatus::PendingPayment,
+            1 => OrderStatus::Paid,
+            2 => OrderStatus::Fulfilled,
+            3 => OrderStatus::Shipped,
+            4 => OrderStatus::Delivered,
+            5 => OrderStatus::Cancelled,
+            6 => OrderStatus::Refunded,
+            _ => panic!("Unknown value: {}", int_status),
+        }
+    }
+
+    pub fn can_transition_to(&self, next: &OrderStatus) -> bool {
+        use OrderStatus::*;
+
+        matches!(
+            (self, next),
+            (PendingPayment, Paid) |
+                (PendingPayment, Cancelled) |
+                (Paid, Fulfilled) |
+                (Paid, Cancelled) |
+                (Paid, Refunded) |
+                (Fulfilled, Shipped) |
+                (Fulfilled, Cancelled) |
+                (Fulfilled, Refunded) |
+                (Shipped, Delivered) |
+                (Delivered, Refunded)
+        )
+    }
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy, Debug)]
+#[repr(u8)]
+pub enum ShippingStatus {
+    Pending,
+    Shipped,
+    Delivered,
+}
+
+#[derive(Queryable, QueryableByName, Serialize, Debug)]
+#[diesel(table_name = crate::schema::order_status_history)]
+pub struct OrderStatusHistory {
+    pub id: i32,
+    pub from_status: Option<i16>,
+    pub to_status: i16,
+    pub created_at: NaiveDateTime,
+    pub order_id: i32,
+    pub changed_by_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::order_status_history)]
+pub struct InsertableOrderStatusHistory {
+    pub from_status: Option<i16>,
+    pub to_status: i16,
+    pub order_id: i32,
+    pub changed_by_id: i32,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct OrderSummary {
+    pub id: i32,
+    pub status: i16,
+    pub total_price: BigDecimal,
+    pub total_discount: Option<BigDecimal>,
+    pub created_at: NaiveDateTime,
+    pub store_id: i32,
+    pub payment_method: String,
+    #[serde(skip)]
+    pub shipping_information_id: i32,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct OrderLineRow {
+    pub id: i32,
+    pub quantity: i32,
+    pub unit_price: BigDecimal,
+    pub product_item_id: i32,
+    pub product_id: i32,
+    pub product_name: String,
+    pub sku: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OrderLine {
+    #[serde(flatten)]
+    pub line: OrderLineRow,
+    pub variants: Vec<CatalogVariant>,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct OrderAddress {
+    pub id: i32,
+    pub address_line1: String,
+    pub address_line2: Option<String>,
+    pub number: String,
+    pub city: String,
+    pub country: String,
+    pub postal_code: String,
+    pub phone_country_code: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct OrderShipping {
+    pub status: i32,
+    pub tracking_number: Option<String>,
+    pub shipping_price: BigDecimal,
+    pub shipping_method: String,
+    pub address: OrderAddress,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OrderDetail {
+    #[serde(flatten)]
+    pub order: OrderSummary,
+    pub shipping: OrderShipping,
+    pub items: Vec<OrderLine>,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct StoreOrderSummary {
+    pub id: i32,
+    pub status: i16,
+    pub total_price: BigDecimal,
+    pub total_discount: Option<BigDecimal>,
+    pub created_at: NaiveDateTime,
+    pub customer_email: String,
+    pub payment_method: String,
+    pub tracking_number: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrderStatus::{ self, * };
+
+    const ALL: [OrderStatus; 7] = [
+        PendingPayment,
+        Paid,
+        Fulfilled,
+        Shipped,
+        Delivered,
+        Cancelled,
+        Refunded,
+    ];
+
+    #[test]
+    fn follows_the_fulfillment_path() {
+        assert!(PendingPayment.can_transition_to(&Paid));
+        assert!(Paid.can_transition_to(&Fulfilled));
+        assert!(Fulfilled.can_transition_to(&Shipped));
+        assert!(Shipped.can_transition_to(&Delivered));
+        assert!(Delivered.can_transition_to(&Refunded));
+    }
+
+    #[test]
+    fn rejects_skipping_or_going_back() {
+        assert!(!PendingPayment.can_transition_to(&Shipped));
+        assert!(!PendingPayment.can_transition_to(&Refunded));
+        assert!(!Paid.can_transition_to(&Delivered));
+        assert!(!Shipped.can_transition_to(&Paid));
+        assert!(!Shipped.can_transition_to(&Cancelled));
+        assert!(!Delivered.can_transition_to(&Shipped));
+    }
+
+    #[test]
+    fn final_statuses_and_self_transitions_are_rejected() {
+        for next in ALL {
+            assert!(!Cancelled.can_transition_to(&next));
+            assert!(!Refunded.can_transition_to(&next));
+        }
+
+        for status in ALL {
+            assert!(!status.can_transition_to(&status));
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_stored_value() {
+        for status in ALL {
+            assert_eq!(OrderStatus::from_i16(status as i16), status);
+        }
+    }
+}
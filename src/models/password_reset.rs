@@ -0,0 +1,7 @@
+use diesel::QueryableByName;
+
+#[derive(QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::password_reset_token)]
+pub struct PasswordResetToken {
+    pub user_id: i32,
+}
@@ -1,15 +1,17 @@
 use chrono::NaiveDateTime;
-use diesel::{ Queryable, Insertable };
+use diesel::{ Queryable, Insertable, QueryableByName };
+use serde::Serialize;
 
-#[derive(Queryable, Debug)]
+#[derive(Serialize, Queryable, QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::payment_method)]
 pub struct PaymentMethod {
     pub id: i32,
     pub name: String,
     pub inactive: bool,
     pub deleted: bool,
     pub created_at: NaiveDateTime,
-    pub store_id: i32,
     pub updated_at: NaiveDateTime,
+    pub store_id: i32,
 }
 
 #[derive(Insertable)]
@@ -1,9 +1,24 @@
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
-use diesel::{ Queryable, Insertable, QueryableByName };
+use diesel::{
+    Queryable,
+    Insertable,
+    QueryableByName,
+    AsChangeset,
+    sql_types::{ Array, Bool, Integer, Nullable, Numeric, Text },
+};
 use serde::Serialize;
-use crate::schema::{ product, product_category, product_item, product_variant };
-#[derive(Queryable, Debug)]
+use super::{ image::ItemImage, response::PaginatedResponse };
+use crate::schema::{
+    product,
+    product_category,
+    product_item,
+    product_item_variant,
+    product_option,
+    product_variant,
+};
+#[derive(Queryable, QueryableByName, Debug)]
+#[diesel(table_name = product)]
 pub struct Product {
     pub id: i32,
     pub name: String,
@@ -35,7 +50,7 @@ pub struct ProductItem {
     pub product_id: i32,
     pub store_id: i32,
     pub updated_at: NaiveDateTime,
-    pub variant_id: Option<i32>,
+    pub weight_grams: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -48,16 +63,29 @@ pub struct InsertableProductItem {
     pub image_url: Option<String>,
     pub product_id: i32,
     pub store_id: i32,
-    pub variant_id: Option<i32>,
+    pub weight_grams: Option<i32>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = product_item)]
+pub struct ProductItemChangeset {
+    pub sku: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<BigDecimal>,
+    pub stock: Option<i32>,
+    pub image_url: Option<String>,
+    pub weight_grams: Option<i32>,
 }
 
 #[derive(Queryable, QueryableByName, Debug, Serialize)]
-#[diesel(table_name = crate::schema::product_variant)]
+#[diesel(table_name = product_category)]
 pub struct ProductCategory {
     pub id: i32,
     pub name: String,
     #[serde(skip)]
     pub store_id: i32,
+    pub slug: String,
+    pub parent_id: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -65,30 +93,270 @@ pub struct ProductCategory {
 pub struct InsertableCategory {
     pub name: String,
     pub store_id: i32,
+    pub slug: String,
+    pub parent_id: Option<i32>,
 }
 
-#[derive(Queryable, Debug)]
+#[derive(QueryableByName, Debug)]
+pub struct CategoryId {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: ProductCategory,
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(QueryableByName, Debug, Serialize)]
+#[diesel(table_name = crate::schema::product_discount)]
 pub struct ProductDiscount {
     pub id: i32,
     pub percentual: BigDecimal,
     pub created_at: NaiveDateTime,
     pub expires_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::product_discount)]
+pub struct InsertableProductDiscount {
+    pub percentual: BigDecimal,
+    pub expires_at: NaiveDateTime,
     pub store_id: i32,
 }
 
+/// An option type, like "Size" or "Color". Its values live in `product_variant`.
 #[derive(Queryable, QueryableByName, Debug, Serialize)]
-#[diesel(table_name = crate::schema::product_variant)]
-pub struct ProductVariant {
+#[diesel(table_name = product_option)]
+pub struct ProductOption {
     pub id: i32,
     pub name: String,
-    pub value: String,
+    pub created_at: NaiveDateTime,
     #[serde(skip)]
     pub store_id: i32,
 }
+
+#[derive(Insertable)]
+#[diesel(table_name = product_option)]
+pub struct InsertableProductOption {
+    pub name: String,
+    pub store_id: i32,
+}
+
+/// A single option value together with the name of its option type.
+#[derive(QueryableByName, Clone, Debug, Serialize)]
+pub struct ProductVariant {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Integer)]
+    pub option_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Text)]
+    pub value: String,
+}
+
 #[derive(Insertable)]
 #[diesel(table_name = product_variant)]
 pub struct InsertableVariant {
+    pub value: String,
+    pub store_id: i32,
+    pub option_id: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProductOptionValue {
+    pub id: i32,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProductOptionDetail {
+    #[serde(flatten)]
+    pub option: ProductOption,
+    pub values: Vec<ProductOptionValue>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = product_item_variant)]
+pub struct InsertableItemVariant {
+    pub product_item_id: i32,
+    pub variant_id: i32,
+    pub option_id: i32,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct ItemVariantRow {
+    #[diesel(sql_type = Integer)]
+    pub product_item_id: i32,
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
     pub name: String,
+    #[diesel(sql_type = Text)]
     pub value: String,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct ItemCombination {
+    #[diesel(sql_type = Integer)]
+    pub product_item_id: i32,
+    #[diesel(sql_type = Array<Integer>)]
+    pub variant_ids: Vec<i32>,
+}
+
+/// A product that still references a category or option value being deleted.
+#[derive(QueryableByName, Serialize, Debug)]
+pub struct BlockingProduct {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Bool)]
+    pub deleted: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GeneratedItems {
+    pub created: Vec<i32>,
+    pub skipped: usize,
+}
+
+#[derive(Queryable, Serialize, Debug)]
+pub struct CatalogProduct {
+    pub id: i32,
+    pub name: String,
     pub store_id: i32,
+    pub category_id: i32,
+    pub category_name: String,
+}
+
+#[derive(Queryable, Debug)]
+pub struct CatalogItemRow {
+    pub id: i32,
+    pub product_id: i32,
+    pub sku: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub price: BigDecimal,
+    pub stock: i32,
+    pub weight_grams: Option<i32>,
+    pub discount_percentual: Option<BigDecimal>,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct ReservedStock {
+    #[diesel(sql_type = Integer)]
+    pub product_item_id: i32,
+    #[diesel(sql_type = Integer)]
+    pub reserved: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CatalogVariant {
+    pub id: i32,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CatalogItem {
+    pub id: i32,
+    pub sku: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub price: BigDecimal,
+    pub effective_price: BigDecimal,
+    pub discount_percentual: Option<BigDecimal>,
+    pub available_stock: i32,
+    pub in_stock: bool,
+    pub weight_grams: Option<i32>,
+    pub variants: Vec<CatalogVariant>,
+    pub images: Vec<ItemImage>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CatalogProductDetail {
+    #[serde(flatten)]
+    pub product: CatalogProduct,
+    pub items: Vec<CatalogItem>,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct MatchedProduct {
+    #[diesel(sql_type = Integer)]
+    pub product_id: i32,
+}
+
+#[derive(QueryableByName, Serialize, Debug)]
+pub struct CategoryFacet {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Integer)]
+    pub count: i32,
+}
+
+#[derive(QueryableByName, Serialize, Debug)]
+pub struct VariantFacet {
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Text)]
+    pub value: String,
+    #[diesel(sql_type = Integer)]
+    pub count: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchFacets {
+    pub categories: Vec<CategoryFacet>,
+    pub variants: Vec<VariantFacet>,
+}
+
+#[derive(Serialize)]
+pub struct ProductSearchResponse {
+    #[serde(flatten)]
+    pub results: PaginatedResponse<CatalogProductDetail>,
+    pub facets: SearchFacets,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportRowError {
+    /// Line of the file, the header being line 1.
+    pub line: u64,
+    pub sku: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Whether the rows were saved, imports with any row error save nothing.
+    pub imported: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(QueryableByName, Debug)]
+pub struct ExportItemRow {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Text)]
+    pub category: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub sku: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub description: Option<String>,
+    #[diesel(sql_type = Numeric)]
+    pub price: BigDecimal,
+    #[diesel(sql_type = Integer)]
+    pub stock: i32,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub image_url: Option<String>,
 }
@@ -0,0 +1,86 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{ Insertable, QueryableByName, sql_types::{ Integer, Numeric } };
+use serde::Serialize;
+use serde_repr::{ Deserialize_repr, Serialize_repr };
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy, Debug)]
+#[repr(u8)]
+pub enum DiscountType {
+    Percentual,
+    Fixed,
+}
+
+impl DiscountType {
+    pub fn from_i16(int_type: i16) -> Self {
+        match int_type {
+            0 => DiscountType::Percentual,
+            1 => DiscountType::Fixed,
+            _ => panic!("Unknown value: {}", int_type),
+        }
+    }
+}
+
+#[derive(QueryableByName, Serialize, Debug)]
+#[diesel(table_name = crate::schema::promo_code)]
+pub struct PromoCode {
+    pub id: i32,
+    pub code: String,
+    pub discount_type: i16,
+    pub amount: BigDecimal,
+    pub min_cart_total: Option<BigDecimal>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_customer: Option<i32>,
+    pub category_ids: Vec<i32>,
+    pub product_ids: Vec<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub inactive: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    #[serde(skip)]
+    pub store_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::promo_code)]
+pub struct InsertablePromoCode {
+    pub code: String,
+    pub discount_type: i16,
+    pub amount: BigDecimal,
+    pub min_cart_total: Option<BigDecimal>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_customer: Option<i32>,
+    pub category_ids: Vec<i32>,
+    pub product_ids: Vec<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub inactive: bool,
+    pub store_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::promo_code_redemption)]
+pub struct InsertablePromoCodeRedemption {
+    pub discount: BigDecimal,
+    pub promo_code_id: i32,
+    pub customer_id: i32,
+    pub order_id: i32,
+}
+
+/// A cart line already priced with its product discount, as seen by the promo engine.
+#[derive(QueryableByName, Debug)]
+pub struct PricedLine {
+    #[diesel(sql_type = Integer)]
+    pub product_id: i32,
+    #[diesel(sql_type = Integer)]
+    pub category_id: i32,
+    #[diesel(sql_type = Numeric)]
+    pub subtotal: BigDecimal,
+}
+
+#[derive(Serialize)]
+pub struct AppliedPromoCode {
+    pub code: String,
+    pub discount: BigDecimal,
+}
@@ -26,9 +26,18 @@ impl<T> ResponseBody<T> {
 #[derive(Serialize)]
 pub struct TokenResponse {
     pub token: String,
+    pub refresh_token: String,
 }
 
 #[derive(Serialize)]
 pub struct IDResponse<T> {
     pub id: T,
 }
+
+#[derive(Serialize)]
+pub struct PaginatedResponse<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
@@ -0,0 +1,73 @@
+use serde_repr::{ Deserialize_repr, Serialize_repr };
+use strum_macros::Display;
+
+#[derive(Serialize_repr, Deserialize_repr, Display, PartialEq, Clone, Copy, Debug)]
+#[strum(serialize_all = "snake_case")]
+#[repr(u8)]
+pub enum StoreRole {
+    Owner,
+    Manager,
+    CatalogEditor,
+    Fulfillment,
+    Support,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Permission {
+    ManageStore,
+    ManageStaff,
+    ViewStaff,
+    ViewCatalog,
+    ManageCatalog,
+    ManagePromotions,
+    ViewOrders,
+    ManageOrders,
+}
+
+impl StoreRole {
+    pub fn from_i16(int_role: i16) -> Self {
+        match int_role {
+            0 => StoreRole::Owner,
+            1 => StoreRole::Manager,
+            2 => StoreRole::CatalogEditor,
+            3 => StoreRole::Fulfillment,
+            4 => StoreRole::Support,
+            _ => panic!("Unknown value: {}", int_role),
+        }
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+
+        match self {
+            StoreRole::Owner =>
+                &[
+                    ManageStore,
+                    ManageStaff,
+                    ViewStaff,
+                    ViewCatalog,
+                    ManageCatalog,
+                    ManagePromotions,
+                    ViewOrders,
+                    ManageOrders,
+                ],
+            StoreRole::Manager =>
+                &[
+                    ManageStore,
+                    ViewStaff,
+                    ViewCatalog,
+                    ManageCatalog,
+                    ManagePromotions,
+                    ViewOrders,
+                    ManageOrders,
+                ],
+            StoreRole::CatalogEditor => &[ViewStaff, ViewCatalog, ManageCatalog, ManagePromotions],
+            StoreRole::Fulfillment => &[ViewStaff, ViewCatalog, ViewOrders, ManageOrders],
+            StoreRole::Support => &[ViewStaff, ViewCatalog, ViewOrders],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
@@ -0,0 +1,8 @@
+use diesel::QueryableByName;
+
+#[derive(QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::user_session)]
+pub struct UserSession {
+    pub id: i32,
+    pub user_id: i32,
+}
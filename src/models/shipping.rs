@@ -1,16 +1,19 @@
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
-use diesel::{ Queryable, Insertable };
+use diesel::{ Queryable, Insertable, QueryableByName };
+use serde::Serialize;
+use serde_repr::{ Deserialize_repr, Serialize_repr };
 
-#[derive(Queryable, Debug)]
+#[derive(Serialize, Queryable, QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::shipping_method)]
 pub struct ShippingMethod {
     pub id: i32,
     pub name: String,
     pub inactive: bool,
     pub deleted: bool,
     pub created_at: NaiveDateTime,
-    pub store_id: i32,
     pub updated_at: NaiveDateTime,
+    pub store_id: i32,
 }
 
 #[derive(Insertable)]
@@ -20,9 +23,67 @@ pub struct InsertableShippingMethod {
     pub store_id: i32,
 }
 
+/// What `min_value`/`max_value` of a rate are compared against. Flat rates ignore them.
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy, Debug)]
+#[repr(u8)]
+pub enum ShippingRateType {
+    Flat,
+    Weight,
+    Total,
+}
+
+impl ShippingRateType {
+    pub fn from_i16(int_type: i16) -> Self {
+        match int_type {
+            0 => ShippingRateType::Flat,
+            1 => ShippingRateType::Weight,
+            2 => ShippingRateType::Total,
+            _ => panic!("Unknown value: {}", int_type),
+        }
+    }
+}
 
+#[derive(Serialize, Queryable, QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::shipping_rate)]
+pub struct ShippingRate {
+    pub id: i32,
+    pub rate_type: i16,
+    pub country: Option<String>,
+    pub min_value: Option<BigDecimal>,
+    pub max_value: Option<BigDecimal>,
+    pub price: BigDecimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    #[serde(skip)]
+    pub shipping_method_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::shipping_rate)]
+pub struct InsertableShippingRate {
+    pub rate_type: i16,
+    pub country: Option<String>,
+    pub min_value: Option<BigDecimal>,
+    pub max_value: Option<BigDecimal>,
+    pub price: BigDecimal,
+    pub shipping_method_id: i32,
+}
 
-#[derive(Queryable, Debug)]
+#[derive(Serialize, Debug)]
+pub struct ShippingMethodDetail {
+    #[serde(flatten)]
+    pub method: ShippingMethod,
+    pub rates: Vec<ShippingRate>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ShippingQuote {
+    pub shipping_method_id: i32,
+    pub shipping_price: BigDecimal,
+}
+
+#[derive(Queryable, QueryableByName, Debug)]
+#[diesel(table_name = crate::schema::shipping_information)]
 pub struct ShippingInformation {
     pub id: i32,
     pub status: i32,
@@ -32,4 +93,29 @@ pub struct ShippingInformation {
     pub shipping_price: BigDecimal,
     pub tracking_number: Option<String>,
     pub updated_at: NaiveDateTime,
-}
\ No newline at end of file
+    pub address_line1: String,
+    pub address_line2: Option<String>,
+    pub number: String,
+    pub city: String,
+    pub country: String,
+    pub postal_code: String,
+    pub phone_country_code: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::shipping_information)]
+pub struct InsertableShippingInformation {
+    pub status: i32,
+    pub shipping_price: BigDecimal,
+    pub address_id: i32,
+    pub shipping_method_id: i32,
+    pub address_line1: String,
+    pub address_line2: Option<String>,
+    pub number: String,
+    pub city: String,
+    pub country: String,
+    pub postal_code: String,
+    pub phone_country_code: Option<String>,
+    pub phone_number: Option<String>,
+}
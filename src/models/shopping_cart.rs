@@ -8,6 +8,7 @@ pub struct ShoppingCart {
     pub created_at: NaiveDateTime,
     pub customer_id: i32,
     pub store_id: i32,
+    pub promo_code_id: Option<i32>,
 }
 #[derive(Insertable)]
 #[diesel(table_name = crate::schema::shopping_cart)]
@@ -0,0 +1,7 @@
+use diesel::{ QueryableByName, sql_types::Integer };
+
+#[derive(QueryableByName, Debug)]
+pub struct ReservedQuantity {
+    #[diesel(sql_type = Integer)]
+    pub reserved: i32,
+}
@@ -1,28 +1,102 @@
 use chrono::NaiveDateTime;
-use diesel::{ Queryable, Insertable, QueryableByName, Identifiable, Selectable };
+use diesel::{ Queryable, Insertable, QueryableByName, Identifiable, Selectable, AsChangeset };
 use serde::Serialize;
+
+use super::{ image::Image, role::StoreRole };
+
 #[derive(Serialize, Queryable, QueryableByName, Identifiable, Selectable, Debug)]
 #[diesel(table_name = crate::schema::store)]
 pub struct Store {
     pub id: i32,
     pub name: String,
-    pub created_at: NaiveDateTime,
     pub logo_url: Option<String>,
+    pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub slug: String,
+    pub currency: String,
+    pub contact_email: Option<String>,
+    pub description: Option<String>,
+    #[serde(skip)]
+    pub logo_image_id: Option<i32>,
 }
 #[derive(Insertable)]
 #[diesel(table_name = crate::schema::store)]
 pub struct InsertableStore {
     pub name: String,
     pub logo_url: Option<String>,
+    pub slug: String,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::store, treat_none_as_null = true)]
+pub struct StoreChangeset {
+    pub name: String,
+    pub logo_url: Option<String>,
+    pub slug: String,
+    pub currency: String,
+    pub contact_email: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StoreProfile {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub logo_url: Option<String>,
+    /// Only set while `logo_url` is the uploaded logo.
+    pub logo_thumbnail_url: Option<String>,
+    pub description: Option<String>,
+    pub contact_email: Option<String>,
+    pub currency: String,
+    /// Only set when the viewer is one of the store's admins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewer_role: Option<StoreRole>,
+}
+
+impl StoreProfile {
+    pub fn new(store: Store, logo: Option<Image>, viewer_role: Option<StoreRole>) -> Self {
+        let logo_thumbnail_url = logo
+            .filter(|logo| store.logo_url.as_ref() == Some(&logo.url))
+            .map(|logo| logo.thumbnail_url);
+
+        StoreProfile {
+            id: store.id,
+            name: store.name,
+            slug: store.slug,
+            logo_url: store.logo_url,
+            logo_thumbnail_url,
+            description: store.description,
+            contact_email: store.contact_email,
+            currency: store.currency,
+            viewer_role,
+        }
+    }
 }
 
-#[derive(Queryable, Serialize, Debug)]
+#[derive(Serialize)]
+pub struct StoreAdmin {
+    pub id: i32,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub role: Option<StoreRole>,
+    pub email_verified: bool,
+    pub last_login: NaiveDateTime,
+}
+
+#[derive(Queryable, QueryableByName, Serialize, Debug)]
+#[diesel(table_name = crate::schema::store_invite)]
 pub struct StoreInvite {
     pub id: String,
     pub valid: bool,
     pub created_at: NaiveDateTime,
     pub store_id: i32,
+    pub role: i16,
+    pub expires_at: NaiveDateTime,
+    pub email: Option<String>,
+    pub max_uses: i32,
+    pub uses: i32,
 }
 
 #[derive(Insertable)]
@@ -30,4 +104,7 @@ pub struct StoreInvite {
 pub struct InsertableStoreInvite {
     pub id: String,
     pub store_id: i32,
+    pub role: i16,
+    pub email: Option<String>,
+    pub max_uses: i32,
 }
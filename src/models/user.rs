@@ -16,6 +16,8 @@ pub struct User {
     pub updated_at: NaiveDateTime,
     pub last_login: NaiveDateTime,
     pub managed_store_id: Option<i32>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub store_role: Option<i16>,
 }
 
 #[derive(Serialize_repr, Deserialize_repr, PartialEq)]
@@ -44,5 +46,6 @@ pub struct InsertableUser {
     pub first_name: String,
     pub last_name: String,
     pub managed_store_id: Option<i32>,
+    pub store_role: Option<i16>,
     pub salt: String,
 }
@@ -1,5 +1,32 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    email_verification_token (id) {
+        id -> Int4,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        user_id -> Int4,
+    }
+}
+
+diesel::table! {
+    image (id) {
+        id -> Int4,
+        url -> Text,
+        thumbnail_url -> Text,
+        storage_key -> Text,
+        thumbnail_key -> Text,
+        content_type -> Text,
+        size_bytes -> Int4,
+        width -> Int4,
+        height -> Int4,
+        created_at -> Timestamp,
+        store_id -> Int4,
+    }
+}
+
 diesel::table! {
     order (id) {
         id -> Int4,
@@ -25,6 +52,28 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    order_status_history (id) {
+        id -> Int4,
+        from_status -> Nullable<Int2>,
+        to_status -> Int2,
+        created_at -> Timestamp,
+        order_id -> Int4,
+        changed_by_id -> Int4,
+    }
+}
+
+diesel::table! {
+    password_reset_token (id) {
+        id -> Int4,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        user_id -> Int4,
+    }
+}
+
 diesel::table! {
     payment_method (id) {
         id -> Int4,
@@ -52,6 +101,8 @@ diesel::table! {
         id -> Int4,
         name -> Text,
         store_id -> Int4,
+        slug -> Text,
+        parent_id -> Nullable<Int4>,
     }
 }
 
@@ -76,19 +127,76 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted -> Bool,
-        variant_id -> Nullable<Int4>,
         product_id -> Int4,
         discount_id -> Nullable<Int4>,
         store_id -> Int4,
+        weight_grams -> Nullable<Int4>,
     }
 }
 
 diesel::table! {
-    product_variant (id) {
+    product_item_image (product_item_id, image_id) {
+        product_item_id -> Int4,
+        image_id -> Int4,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    product_item_variant (product_item_id, variant_id) {
+        product_item_id -> Int4,
+        variant_id -> Int4,
+        option_id -> Int4,
+    }
+}
+
+diesel::table! {
+    product_option (id) {
         id -> Int4,
         name -> Text,
+        created_at -> Timestamp,
+        store_id -> Int4,
+    }
+}
+
+diesel::table! {
+    product_variant (id) {
+        id -> Int4,
         value -> Text,
         store_id -> Int4,
+        option_id -> Int4,
+    }
+}
+
+diesel::table! {
+    promo_code (id) {
+        id -> Int4,
+        code -> Text,
+        discount_type -> Int2,
+        amount -> Numeric,
+        min_cart_total -> Nullable<Numeric>,
+        max_uses -> Nullable<Int4>,
+        max_uses_per_customer -> Nullable<Int4>,
+        category_ids -> Array<Int4>,
+        product_ids -> Array<Int4>,
+        starts_at -> Nullable<Timestamp>,
+        expires_at -> Nullable<Timestamp>,
+        inactive -> Bool,
+        deleted -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        store_id -> Int4,
+    }
+}
+
+diesel::table! {
+    promo_code_redemption (id) {
+        id -> Int4,
+        discount -> Numeric,
+        created_at -> Timestamp,
+        promo_code_id -> Int4,
+        customer_id -> Int4,
+        order_id -> Int4,
     }
 }
 
@@ -102,6 +210,14 @@ diesel::table! {
         shipping_price -> Numeric,
         address_id -> Int4,
         shipping_method_id -> Int4,
+        address_line1 -> Text,
+        address_line2 -> Nullable<Text>,
+        number -> Text,
+        city -> Text,
+        country -> Text,
+        postal_code -> Text,
+        phone_country_code -> Nullable<Text>,
+        phone_number -> Nullable<Text>,
     }
 }
 
@@ -117,12 +233,38 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    shipping_rate (id) {
+        id -> Int4,
+        rate_type -> Int2,
+        country -> Nullable<Text>,
+        min_value -> Nullable<Numeric>,
+        max_value -> Nullable<Numeric>,
+        price -> Numeric,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        shipping_method_id -> Int4,
+    }
+}
+
 diesel::table! {
     shopping_cart (id) {
         id -> Int4,
         customer_id -> Int4,
         store_id -> Int4,
         created_at -> Timestamp,
+        promo_code_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    stock_reservation (id) {
+        id -> Int4,
+        quantity -> Int4,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        product_item_id -> Int4,
+        order_item_id -> Int4,
     }
 }
 
@@ -133,6 +275,11 @@ diesel::table! {
         logo_url -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        slug -> Text,
+        currency -> Text,
+        contact_email -> Nullable<Text>,
+        description -> Nullable<Text>,
+        logo_image_id -> Nullable<Int4>,
     }
 }
 
@@ -142,6 +289,11 @@ diesel::table! {
         valid -> Bool,
         created_at -> Timestamp,
         store_id -> Int4,
+        role -> Int2,
+        expires_at -> Timestamp,
+        email -> Nullable<Text>,
+        max_uses -> Int4,
+        uses -> Int4,
     }
 }
 
@@ -159,6 +311,8 @@ diesel::table! {
         updated_at -> Timestamp,
         last_login -> Timestamp,
         managed_store_id -> Nullable<Int4>,
+        email_verified_at -> Nullable<Timestamp>,
+        store_role -> Nullable<Int2>,
     }
 }
 
@@ -180,6 +334,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_session (id) {
+        id -> Int4,
+        refresh_token_hash -> Text,
+        previous_token_hash -> Nullable<Text>,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        user_id -> Int4,
+    }
+}
+
+diesel::joinable!(email_verification_token -> user (user_id));
+diesel::joinable!(image -> store (store_id));
 diesel::joinable!(order -> payment_method (payment_method_id));
 diesel::joinable!(order -> shipping_information (shipping_information_id));
 diesel::joinable!(order -> store (store_id));
@@ -187,6 +356,9 @@ diesel::joinable!(order -> user (customer_id));
 diesel::joinable!(order_item -> order (order_id));
 diesel::joinable!(order_item -> product_item (product_item_id));
 diesel::joinable!(order_item -> shopping_cart (shopping_cart_id));
+diesel::joinable!(order_status_history -> order (order_id));
+diesel::joinable!(order_status_history -> user (changed_by_id));
+diesel::joinable!(password_reset_token -> user (user_id));
 diesel::joinable!(payment_method -> store (store_id));
 diesel::joinable!(product -> product_category (category_id));
 diesel::joinable!(product -> store (store_id));
@@ -194,32 +366,59 @@ diesel::joinable!(product_category -> store (store_id));
 diesel::joinable!(product_discount -> store (store_id));
 diesel::joinable!(product_item -> product (product_id));
 diesel::joinable!(product_item -> product_discount (discount_id));
-diesel::joinable!(product_item -> product_variant (variant_id));
 diesel::joinable!(product_item -> store (store_id));
+diesel::joinable!(product_item_image -> image (image_id));
+diesel::joinable!(product_item_image -> product_item (product_item_id));
+diesel::joinable!(product_item_variant -> product_item (product_item_id));
+diesel::joinable!(product_item_variant -> product_option (option_id));
+diesel::joinable!(product_item_variant -> product_variant (variant_id));
+diesel::joinable!(product_option -> store (store_id));
+diesel::joinable!(product_variant -> product_option (option_id));
 diesel::joinable!(product_variant -> store (store_id));
+diesel::joinable!(promo_code -> store (store_id));
+diesel::joinable!(promo_code_redemption -> order (order_id));
+diesel::joinable!(promo_code_redemption -> promo_code (promo_code_id));
+diesel::joinable!(promo_code_redemption -> user (customer_id));
 diesel::joinable!(shipping_information -> shipping_method (shipping_method_id));
 diesel::joinable!(shipping_information -> user_address (address_id));
 diesel::joinable!(shipping_method -> store (store_id));
+diesel::joinable!(shipping_rate -> shipping_method (shipping_method_id));
+diesel::joinable!(shopping_cart -> promo_code (promo_code_id));
 diesel::joinable!(shopping_cart -> store (store_id));
 diesel::joinable!(shopping_cart -> user (customer_id));
+diesel::joinable!(stock_reservation -> order_item (order_item_id));
+diesel::joinable!(stock_reservation -> product_item (product_item_id));
 diesel::joinable!(store_invite -> store (store_id));
 diesel::joinable!(user -> store (managed_store_id));
 diesel::joinable!(user_address -> user (user_id));
+diesel::joinable!(user_session -> user (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    email_verification_token,
+    image,
     order,
     order_item,
+    order_status_history,
+    password_reset_token,
     payment_method,
     product,
     product_category,
     product_discount,
     product_item,
+    product_item_image,
+    product_item_variant,
+    product_option,
     product_variant,
+    promo_code,
+    promo_code_redemption,
     shipping_information,
     shipping_method,
+    shipping_rate,
     shopping_cart,
+    stock_reservation,
     store,
     store_invite,
     user,
     user_address,
+    user_session,
 );
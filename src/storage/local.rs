@@ -0,0 +1,60 @@
+use std::{ env, fs, io::ErrorKind, path::PathBuf };
+
+use actix_files::Files;
+
+use super::FileStorage;
+use crate::error::ServiceError;
+
+const DEFAULT_UPLOAD_DIR: &str = "uploads";
+const SERVED_PATH: &str = "/uploads";
+
+/// Writes uploads into `UPLOAD_DIR`, the API serves them back under `/uploads`.
+pub struct LocalFileStorage {
+    dir: PathBuf,
+    public_url: String,
+}
+
+impl LocalFileStorage {
+    pub fn from_env() -> LocalFileStorage {
+        LocalFileStorage {
+            dir: Self::dir_from_env(),
+            // set it to the full URL when clients can't resolve paths against the API host
+            public_url: env
+                ::var("UPLOAD_PUBLIC_URL")
+                .unwrap_or(SERVED_PATH.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+
+    fn dir_from_env() -> PathBuf {
+        env::var("UPLOAD_DIR").unwrap_or(DEFAULT_UPLOAD_DIR.to_string()).into()
+    }
+
+    pub fn files_service() -> Files {
+        Files::new(SERVED_PATH, Self::dir_from_env())
+    }
+}
+
+impl FileStorage for LocalFileStorage {
+    fn put(&self, key: &str, bytes: &[u8], _content_type: &str) -> Result<String, ServiceError> {
+        let path = self.dir.join(key);
+
+        let write_result = fs
+            ::create_dir_all(path.parent().unwrap_or(&self.dir))
+            .and_then(|_| fs::write(&path, bytes));
+
+        match write_result {
+            Ok(_) => Ok(format!("{}/{}", self.public_url, key)),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ServiceError> {
+        match fs::remove_file(self.dir.join(key)) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+pub mod local;
+pub mod s3;
+
+use std::{ env, sync::Arc };
+
+use actix_web::web;
+
+use crate::error::ServiceError;
+use local::LocalFileStorage;
+use s3::S3FileStorage;
+
+pub trait FileStorage: Send + Sync {
+    /// Saves `bytes` under `key` and returns the public URL of the file.
+    fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<String, ServiceError>;
+    fn delete(&self, key: &str) -> Result<(), ServiceError>;
+}
+
+fn uses_s3() -> bool {
+    matches!(env::var("STORAGE_BACKEND").as_deref(), Ok("s3"))
+}
+
+/// Picks the backend from `STORAGE_BACKEND`. Anything other than `s3` keeps uploads on
+/// the local filesystem, so development doesn't need a bucket.
+pub fn storage_from_env() -> Arc<dyn FileStorage> {
+    if uses_s3() {
+        Arc::new(S3FileStorage::from_env())
+    } else {
+        Arc::new(LocalFileStorage::from_env())
+    }
+}
+
+/// Serves the local backend's uploads, an S3 bucket serves its files itself.
+pub fn serve_local_files(cfg: &mut web::ServiceConfig) {
+    if !uses_s3() {
+        cfg.service(LocalFileStorage::files_service());
+    }
+}
@@ -0,0 +1,173 @@
+use std::env;
+
+use chrono::Utc;
+use hmac::{ Hmac, Mac };
+use sha2::{ Digest, Sha256 };
+
+use super::FileStorage;
+use crate::error::ServiceError;
+
+/// Stores uploads in a bucket of any S3-compatible service (AWS, MinIO, R2...), using
+/// path-style URLs and SigV4 signed requests. Objects must be made readable through the
+/// bucket policy, since uploads don't set an ACL.
+pub struct S3FileStorage {
+    endpoint: String,
+    host: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    public_url: String,
+}
+
+impl S3FileStorage {
+    pub fn from_env() -> S3FileStorage {
+        let endpoint = env
+            ::var("S3_ENDPOINT")
+            .expect("S3_ENDPOINT must be set")
+            .trim_end_matches('/')
+            .to_string();
+
+        let host = endpoint
+            .split_once("://")
+            .map(|(_, host)| host)
+            .unwrap_or(&endpoint)
+            .to_string();
+
+        let bucket = env::var("S3_BUCKET").expect("S3_BUCKET must be set");
+
+        S3FileStorage {
+            public_url: env
+                ::var("S3_PUBLIC_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or(format!("{}/{}", endpoint, bucket)),
+            region: env::var("S3_REGION").unwrap_or("us-east-1".to_string()),
+            access_key: env::var("S3_ACCESS_KEY").expect("S3_ACCESS_KEY must be set"),
+            secret_key: env::var("S3_SECRET_KEY").expect("S3_SECRET_KEY must be set"),
+            endpoint,
+            host,
+            bucket,
+        }
+    }
+
+    fn object_path(&self, key: &str) -> String {
+        let encoded_key: Vec<String> = key.split('/').map(uri_encode).collect();
+
+        format!("/{}/{}", uri_encode(&self.bucket), encoded_key.join("/"))
+    }
+
+    /// Sends a SigV4 signed request for `key`. `headers` must be lowercase and sorted.
+    fn send(
+        &self,
+        method: &str,
+        key: &str,
+        headers: &[(&str, &str)],
+        body: &[u8]
+    ) -> Result<(), ServiceError> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(body));
+        let path = self.object_path(key);
+
+        let mut signed: Vec<(&str, &str)> = headers.to_vec();
+        signed.extend([
+            ("host", self.host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ]);
+        signed.sort_unstable();
+
+        let canonical_headers: String = signed
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+
+        let signed_headers = signed
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method,
+            path,
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = [date.as_str(), self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(format!("AWS4{}", self.secret_key).into_bytes(), |key, part| hmac(&key, part));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key,
+            scope,
+            signed_headers,
+            hex::encode(hmac(&signing_key, &string_to_sign))
+        );
+
+        let mut request = ureq
+            ::request(method, &format!("{}{}", self.endpoint, path))
+            .set("authorization", &authorization);
+
+        for (name, value) in signed.iter().filter(|(name, _)| *name != "host") {
+            request = request.set(name, value);
+        }
+
+        match request.send_bytes(body) {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(status, response)) =>
+                Err(ServiceError::InternalServerError {
+                    error_message: format!(
+                        "Storage responded {}: {}",
+                        status,
+                        response.into_string().unwrap_or_default()
+                    ),
+                }),
+            Err(e) => Err(ServiceError::InternalServerError { error_message: e.to_string() }),
+        }
+    }
+}
+
+impl FileStorage for S3FileStorage {
+    fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<String, ServiceError> {
+        self.send("PUT", key, &[("content-type", content_type)], bytes)?;
+
+        Ok(format!("{}/{}", self.public_url, key))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ServiceError> {
+        self.send("DELETE", key, &[], &[])
+    }
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac: Hmac<Sha256> = Hmac::new_from_slice(key).unwrap();
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes everything but the characters SigV4 leaves unreserved.
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' =>
+                (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
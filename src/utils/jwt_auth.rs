@@ -9,14 +9,21 @@ pub struct TokenClaims {
     pub sub: String,
     pub first_name: String,
     pub last_name: String,
-    pub managed_store_id: Option<i32>
+    pub managed_store_id: Option<i32>,
+    pub sid: i32,
 }
 
+const DEFAULT_ACCESS_TOKEN_MINUTES: u64 = 15;
+
 impl TokenClaims {
-    pub fn new(type_: i32, sub: &str, first_name: &str, last_name: &str, managed_store_id: Option<i32>) -> Self {
+    pub fn new(type_: i32, sub: &str, first_name: &str, last_name: &str, managed_store_id: Option<i32>, sid: i32) -> Self {
+        let access_token_minutes = std::env::var("ACCESS_TOKEN_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse().ok())
+            .unwrap_or(DEFAULT_ACCESS_TOKEN_MINUTES);
+
         let mut timer = SystemTime::now();
-        //7 days duration
-        timer = timer + Duration::from_secs(604800);
+        timer = timer + Duration::from_secs(access_token_minutes * 60);
 
         let exp_time = timer.duration_since(UNIX_EPOCH).unwrap().as_secs();
 
@@ -25,6 +32,7 @@ impl TokenClaims {
             last_name: last_name.to_string(),
             sub: sub.to_string(),
             managed_store_id, 
+            sid,
             type_,
             exp: exp_time as usize,
         }
@@ -1,3 +1,6 @@
 pub mod jwt_auth;
 pub mod password_hash;
-pub mod validation;
\ No newline at end of file
+pub mod validation;
+pub mod token;
+pub mod slug;
+pub mod upload;
\ No newline at end of file
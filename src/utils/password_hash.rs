@@ -1,28 +1,71 @@
+use argon2::{
+    password_hash::{
+        rand_core::OsRng,
+        PasswordHash as PhcHash,
+        PasswordHasher as _,
+        PasswordVerifier as _,
+        SaltString,
+    },
+    Argon2,
+};
 use hmac::{Hmac, Mac};
-use rand::distributions::{Alphanumeric, DistString};
 use sha2::Sha256;
-pub struct PasswordHash {
-    pub password_hash: String,
-    pub salt: String,
+
+/// A way of turning passwords into stored hashes. New hashes are always created with
+/// the default scheme, the others are only kept around to verify existing users.
+pub trait PasswordScheme {
+    fn hash(&self, password: &str) -> PasswordHash;
+    fn verify(&self, stored: &PasswordHash, password_attemp: &str) -> bool;
+    fn handles(&self, stored: &PasswordHash) -> bool;
 }
 
-impl PasswordHash {
-    pub fn new(password_hash: &str, salt: &str) -> PasswordHash {
+/// Argon2id with the crate defaults, stored as a PHC string. The salt lives inside the
+/// PHC string, so the `salt` column is left empty.
+pub struct Argon2idScheme;
+
+impl PasswordScheme for Argon2idScheme {
+    fn hash(&self, password: &str) -> PasswordHash {
+        let salt = SaltString::generate(&mut OsRng);
+
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .expect("Error hashing password")
+            .to_string();
+
         PasswordHash {
-            password_hash: password_hash.to_string(),
-            salt: salt.to_string(),
+            password_hash,
+            salt: String::new(),
         }
     }
-    pub fn create_hash(password: &str) -> PasswordHash {
+
+    fn verify(&self, stored: &PasswordHash, password_attemp: &str) -> bool {
+        match PhcHash::new(&stored.password_hash) {
+            Ok(parsed_hash) => Argon2::default()
+                .verify_password(password_attemp.as_bytes(), &parsed_hash)
+                .is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    fn handles(&self, stored: &PasswordHash) -> bool {
+        stored.password_hash.starts_with("$argon2id$")
+    }
+}
+
+/// HMAC-SHA256 keyed by a per-user salt, used before Argon2id was introduced.
+pub struct LegacyHmacScheme;
+
+impl PasswordScheme for LegacyHmacScheme {
+    fn hash(&self, password: &str) -> PasswordHash {
+        use rand::distributions::{Alphanumeric, DistString};
+
         let salt = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
 
         let mut mac: Hmac<Sha256> = Hmac::new_from_slice(salt.as_bytes()).unwrap();
 
         mac.update(password.as_bytes());
 
-        let hash_bytes = mac.finalize().into_bytes().to_vec();
-
-        let password_hash = hex::encode(hash_bytes);
+        let password_hash = hex::encode(mac.finalize().into_bytes());
 
         PasswordHash {
             salt,
@@ -30,15 +73,90 @@ impl PasswordHash {
         }
     }
 
-    pub fn verify_password(&self, password_attemp: &str) -> bool {
-        let mut mac: Hmac<Sha256> = Hmac::new_from_slice(&self.salt.as_bytes()).unwrap();
+    fn verify(&self, stored: &PasswordHash, password_attemp: &str) -> bool {
+        let expected_hash = match hex::decode(&stored.password_hash) {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
+
+        let mut mac: Hmac<Sha256> = Hmac::new_from_slice(stored.salt.as_bytes()).unwrap();
 
         mac.update(password_attemp.as_bytes());
 
-        let hash_bytes = mac.finalize().into_bytes().to_vec();
+        // verify_slice compares in constant time
+        mac.verify_slice(&expected_hash).is_ok()
+    }
+
+    fn handles(&self, stored: &PasswordHash) -> bool {
+        !stored.salt.is_empty() && !stored.password_hash.starts_with('$')
+    }
+}
+
+const DEFAULT_SCHEME: Argon2idScheme = Argon2idScheme;
+
+const SCHEMES: [&dyn PasswordScheme; 2] = [&Argon2idScheme, &LegacyHmacScheme];
+
+pub struct PasswordHash {
+    pub password_hash: String,
+    pub salt: String,
+}
+
+impl PasswordHash {
+    pub fn new(password_hash: &str, salt: &str) -> PasswordHash {
+        PasswordHash {
+            password_hash: password_hash.to_string(),
+            salt: salt.to_string(),
+        }
+    }
+
+    pub fn create_hash(password: &str) -> PasswordHash {
+        DEFAULT_SCHEME.hash(password)
+    }
+
+    pub fn verify_password(&self, password_attemp: &str) -> bool {
+        SCHEMES
+            .iter()
+            .find(|scheme| scheme.handles(self))
+            .is_some_and(|scheme| scheme.verify(self, password_attemp))
+    }
+
+    /// Whether this hash was created with a scheme other than the default one and
+    /// should be replaced the next time the plain password is available.
+    pub fn needs_rehash(&self) -> bool {
+        !DEFAULT_SCHEME.handles(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argon2id_round_trip() {
+        let stored = PasswordHash::create_hash("correct horse");
+
+        assert!(Argon2idScheme.handles(&stored));
+        assert!(stored.salt.is_empty());
+        assert!(stored.verify_password("correct horse"));
+        assert!(!stored.needs_rehash());
+    }
+
+    #[test]
+    fn legacy_hmac_verifies_and_needs_rehash() {
+        let stored = LegacyHmacScheme.hash("correct horse");
+
+        assert!(LegacyHmacScheme.handles(&stored));
+        assert!(!Argon2idScheme.handles(&stored));
+        assert!(stored.verify_password("correct horse"));
+        assert!(stored.needs_rehash());
+    }
 
-        let attempt_hash = hex::encode(hash_bytes);
+    #[test]
+    fn wrong_password_fails_for_both_schemes() {
+        let argon2id = PasswordHash::create_hash("correct horse");
+        let legacy = LegacyHmacScheme.hash("correct horse");
 
-        attempt_hash.eq(&self.password_hash)
+        assert!(!argon2id.verify_password("battery staple"));
+        assert!(!legacy.verify_password("battery staple"));
     }
 }
@@ -0,0 +1,61 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    pub static ref REGEX_SLUG: Regex = Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap();
+}
+
+/// Lowercases `value` and joins its alphanumeric runs with dashes, so
+/// "Bob's Shop & Co." becomes "bob-s-shop-co".
+pub fn slugify(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// First of `base`, `base-2`, `base-3`, ... that isn't in `taken`.
+pub fn numbered_slug(base: &str, taken: &[String]) -> String {
+    (1..)
+        .map(|n| if n == 1 { base.to_string() } else { format!("{}-{}", base, n) })
+        .find(|candidate| !taken.iter().any(|slug| slug == candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punctuation_and_whitespace_become_single_dashes() {
+        assert_eq!(slugify("Bob's Shop & Co."), "bob-s-shop-co");
+        assert_eq!(slugify("  red   shoes  "), "red-shoes");
+        assert_eq!(slugify("--already-a-slug--"), "already-a-slug");
+    }
+
+    #[test]
+    fn uppercase_is_lowered_and_digits_are_kept() {
+        assert_eq!(slugify("T-Shirt XL 2023"), "t-shirt-xl-2023");
+    }
+
+    #[test]
+    fn empty_or_symbol_only_input_gives_empty_slug() {
+        assert_eq!(slugify(""), "");
+        assert_eq!(slugify("&*!? ..."), "");
+    }
+
+    #[test]
+    fn non_ascii_characters_are_separators() {
+        assert_eq!(slugify("Café Crème"), "caf-cr-me");
+    }
+
+    #[test]
+    fn numbered_slug_picks_the_first_free_number() {
+        let taken = vec!["shoes".to_string(), "shoes-2".to_string(), "shoes-4".to_string()];
+
+        assert_eq!(numbered_slug("boots", &taken), "boots");
+        assert_eq!(numbered_slug("shoes", &taken), "shoes-3");
+    }
+}
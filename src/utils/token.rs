@@ -0,0 +1,12 @@
+use rand::distributions::{ Alphanumeric, DistString };
+use sha2::{ Digest, Sha256 };
+
+pub fn generate_token() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), 64)
+}
+
+/// Tokens are stored hashed so a leaked table can't be replayed. They are already
+/// random and long, so a plain SHA-256 is enough here.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,149 @@
+use std::{ env, io::Cursor };
+
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+use image::{ io::{ Limits, Reader }, DynamicImage, ImageFormat, ImageOutputFormat };
+
+use crate::error::ServiceError;
+
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+/// Bounds decoding, so a small file can't claim a huge canvas.
+const MAX_IMAGE_DIMENSION: u32 = 8000;
+const THUMBNAIL_SIZE: u32 = 320;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+pub struct UploadedFile {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+pub struct ProcessedImage {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail_content_type: &'static str,
+    pub thumbnail_extension: &'static str,
+    pub thumbnail: Vec<u8>,
+}
+
+fn max_upload_bytes() -> usize {
+    env::var("MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|bytes| bytes.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES)
+}
+
+fn bad_request(message: &str) -> ServiceError {
+    ServiceError::BadRequest { error_message: message.to_string() }
+}
+
+/// Reads the files sent as `field_name` parts of a multipart body, other parts are skipped.
+pub async fn read_files(
+    mut payload: Multipart,
+    field_name: &str,
+    max_files: usize
+) -> Result<Vec<UploadedFile>, ServiceError> {
+    let max_bytes = max_upload_bytes();
+    let mut files = Vec::new();
+
+    while let Some(mut field) = payload.try_next().await.map_err(|e| bad_request(&e.to_string()))? {
+        if field.name() != Some(field_name) {
+            continue;
+        }
+
+        if files.len() == max_files {
+            return Err(bad_request(&format!("At most {} files can be uploaded at once", max_files)));
+        }
+
+        let content_type = match field.content_type() {
+            Some(mime) => mime.essence_str().to_string(),
+            None => {
+                return Err(bad_request("Uploaded files must have a content type"));
+            }
+        };
+
+        let mut bytes = Vec::new();
+
+        while let Some(chunk) = field.try_next().await.map_err(|e| bad_request(&e.to_string()))? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(
+                    bad_request(&format!("Files can't be larger than {} KB", max_bytes / 1024))
+                );
+            }
+
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if bytes.is_empty() {
+            return Err(bad_request("Uploaded files can't be empty"));
+        }
+
+        files.push(UploadedFile { content_type, bytes });
+    }
+
+    if files.is_empty() {
+        return Err(bad_request(&format!("Missing {} file", field_name)));
+    }
+
+    Ok(files)
+}
+
+/// Checks the file really is a JPEG, PNG or WebP image of its declared type and renders
+/// its thumbnail. JPEG thumbnails stay JPEG, the rest become PNG to keep transparency.
+pub fn process_image(file: UploadedFile) -> Result<ProcessedImage, ServiceError> {
+    let (format, content_type, extension) = match file.content_type.as_str() {
+        "image/jpeg" => (ImageFormat::Jpeg, "image/jpeg", "jpg"),
+        "image/png" => (ImageFormat::Png, "image/png", "png"),
+        "image/webp" => (ImageFormat::WebP, "image/webp", "webp"),
+        _ => {
+            return Err(bad_request("Only JPEG, PNG and WebP images are allowed"));
+        }
+    };
+
+    if image::guess_format(&file.bytes).ok() != Some(format) {
+        return Err(bad_request(&format!("File content isn't a valid {}", file.content_type)));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+
+    let mut reader = Reader::with_format(Cursor::new(&file.bytes), format);
+    reader.limits(limits);
+
+    let image = reader
+        .decode()
+        .map_err(|e| bad_request(&format!("Image couldn't be read: {}", e)))?;
+
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+
+    let (thumbnail, thumbnail_format, thumbnail_content_type, thumbnail_extension) = match format {
+        ImageFormat::Jpeg =>
+            (
+                DynamicImage::ImageRgb8(thumbnail.to_rgb8()),
+                ImageOutputFormat::Jpeg(THUMBNAIL_JPEG_QUALITY),
+                "image/jpeg",
+                "jpg",
+            ),
+        _ => (thumbnail, ImageOutputFormat::Png, "image/png", "png"),
+    };
+
+    let mut thumbnail_bytes = Cursor::new(Vec::new());
+
+    thumbnail
+        .write_to(&mut thumbnail_bytes, thumbnail_format)
+        .map_err(|e| ServiceError::InternalServerError { error_message: e.to_string() })?;
+
+    Ok(ProcessedImage {
+        content_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+        bytes: file.bytes,
+        thumbnail_content_type,
+        thumbnail_extension,
+        thumbnail: thumbnail_bytes.into_inner(),
+    })
+}
@@ -0,0 +1 @@
+const DEFAULT_ROUNDING_MODE: RoundingMode = RoundingMode::HalfEven;
\ No newline at end of file